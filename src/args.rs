@@ -1,5 +1,3 @@
-use std::fmt::Display;
-
 use bevy::color::Srgba;
 use clap::{Parser, Subcommand};
 
@@ -7,9 +5,6 @@ use clap::{Parser, Subcommand};
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Args {
-    #[command(subcommand)]
-    pub variant: Option<Variant>,
-
     #[arg(
         short,
         long = "bg",
@@ -35,31 +30,48 @@ pub struct Args {
         help = "Prints command for cancelling ttysvr in current shell."
     )]
     pub cancel: bool,
-}
 
-#[derive(Subcommand)]
-pub enum Variant {
-    Bubbles,
-    Logo {
-        #[command(subcommand)]
-        variant: Option<LogoVariant>,
-    },
-    Maze {
-        #[command(subcommand)]
-        variant: Option<MazeVariant>,
-    },
-}
+    #[arg(
+        long,
+        global = true,
+        help = "Shows frame rate and entity count in the corner."
+    )]
+    pub debug: bool,
 
-#[derive(Subcommand)]
-pub enum LogoVariant {
-    Dvd,
-    Tty,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        name = "CHOICES",
+        help = "Picks a variant at random only from CHOICES, like maze or logo:dvd."
+    )]
+    pub random_from: Vec<String>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        name = "EXCLUDED",
+        help = "Never picks EXCLUDED at random, like logo or maze:hedge."
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(long, help = "Never picks the same variant at random twice in a row.")]
+    pub no_repeat: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
+/// Commands that sit alongside the screensavers, which are added to the command line by the
+/// `ScreensaverRegistry`.
 #[derive(Subcommand)]
-pub enum MazeVariant {
-    Brick,
-    Hedge,
+pub enum Command {
+    /// Lists every screensaver with its subvariants and options.
+    List {
+        #[arg(long, help = "Prints the list as JSON.")]
+        json: bool,
+    },
+    /// Checks the terminal, graphics and shell setup, and suggests fixes for any problems.
+    Doctor,
 }
 
 #[derive(Clone)]
@@ -74,49 +86,3 @@ impl From<String> for ColorPreference {
         ColorPreference(Srgba::NONE)
     }
 }
-
-impl Display for Variant {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Variant::Bubbles => write!(f, "bubbles"),
-            Variant::Logo { variant } => {
-                if let Some(variant) = variant {
-                    write!(f, "logo {variant}")
-                } else {
-                    write!(f, "logo")
-                }
-            }
-            Variant::Maze { variant } => {
-                if let Some(variant) = variant {
-                    write!(f, "maze {variant}")
-                } else {
-                    write!(f, "maze")
-                }
-            }
-        }
-    }
-}
-
-impl Display for LogoVariant {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            LogoVariant::Dvd => write!(f, "dvd"),
-            LogoVariant::Tty => write!(f, "tty"),
-        }
-    }
-}
-
-impl Display for MazeVariant {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            MazeVariant::Brick => write!(f, "brick"),
-            MazeVariant::Hedge => write!(f, "hedge"),
-        }
-    }
-}
-
-impl Display for ColorPreference {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.to_hex())
-    }
-}
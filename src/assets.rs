@@ -1,6 +1,12 @@
+use std::path::Path;
+
 use bevy::asset::embedded_asset;
+use bevy::asset::io::{AssetSource, AssetSourceBuilder};
+use bevy::asset::AssetPath;
 use bevy::prelude::*;
 
+const FILE_SOURCE: &str = "file";
+
 pub(super) fn plugin(app: &mut App) {
     let prefix = "src/";
     embedded_asset!(app, prefix, "../assets/bubble.png");
@@ -11,4 +17,25 @@ pub(super) fn plugin(app: &mut App) {
     embedded_asset!(app, prefix, "../assets/maze_floor.png");
     embedded_asset!(app, prefix, "../assets/maze_ceiling_brick.png");
     embedded_asset!(app, prefix, "../assets/maze_ceiling_hedge.png");
+    embedded_asset!(app, prefix, "../assets/toaster.png");
+    embedded_asset!(app, prefix, "../assets/toast.png");
+}
+
+/// Registers an asset source for files supplied by the user. Has to run before the `AssetPlugin`
+/// is added.
+pub(super) fn register_sources(app: &mut App) {
+    app.register_asset_source(
+        FILE_SOURCE,
+        AssetSourceBuilder::default().with_reader(AssetSource::get_default_reader("".into())),
+    );
+}
+
+/// Asset path for a file on disk, such as a custom logo image. `path` should be absolute.
+///
+/// The path is kept as a path rather than formatted into a string, which bevy would parse again,
+/// so that Windows drive letters and backslashes or a `#` in a file name come through intact.
+pub fn file_asset_path(path: &Path) -> AssetPath<'static> {
+    AssetPath::from_path(path)
+        .into_owned()
+        .with_source(FILE_SOURCE)
 }
@@ -1,18 +1,26 @@
 use avian2d::{
     math::{Scalar, Vector},
-    prelude::{Collider, Friction, Gravity, LinearVelocity, LockedAxes, RigidBody},
+    prelude::{
+        Collider, ExternalImpulse, Friction, Gravity, LinearVelocity, LockedAxes, RigidBody,
+    },
     PhysicsPlugins,
 };
 use bevy::prelude::*;
-use bevy_ratatui::event::ResizeEvent;
+use bevy_ratatui::event::{MouseEvent, ResizeEvent};
 use bevy_ratatui_camera::RatatuiCamera;
+use clap::{ArgMatches, Args, FromArgMatches, ValueEnum};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use ratatui::crossterm::event::{MouseButton, MouseEventKind};
+
+use crate::{common::MouseInteraction, Screensaver};
 
 const ORTHO_SCALING: f32 = 0.5;
 const BUBBLE_RATE: f32 = 0.33;
 const BUBBLE_MAX_SPEED: f32 = 24.;
 const BUBBLE_RADIUS: f32 = 9.;
+const BUBBLE_PUSH_RADIUS: f32 = 40.;
+const BUBBLE_PUSH_STRENGTH: f32 = 400.;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(PhysicsPlugins::default().with_length_unit(128.))
@@ -27,14 +35,77 @@ pub(super) fn plugin(app: &mut App) {
                 handle_resize_system,
                 bubble_movement_system,
                 bubble_color_system,
+                bubble_mouse_system.run_if(resource_exists::<BubbleMouse>),
             ),
         );
 }
 
+#[derive(Clone, Default)]
+pub struct BubblesSettings {
+    /// React to the mouse instead of exiting the screensaver.
+    pub mouse: Option<BubbleMouseMode>,
+}
+
+#[derive(Args)]
+struct BubblesArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        name = "MODE",
+        help = "Bubbles react to the mouse instead of exiting the screensaver."
+    )]
+    mouse: Option<BubbleMouseMode>,
+}
+
+impl Screensaver for BubblesSettings {
+    fn name(&self) -> &'static str {
+        "bubbles"
+    }
+
+    fn about(&self) -> &'static str {
+        "Colorful bubbles bounce around the screen."
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        BubblesArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        _subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let BubblesArgs { mouse } = BubblesArgs::from_arg_matches(matches)?;
+        self.mouse = mouse;
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        if let Some(mouse_mode) = self.mouse {
+            app.insert_resource(BubbleMouse(mouse_mode))
+                .init_resource::<MouseInteraction>();
+        }
+
+        app.add_plugins(plugin);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum BubbleMouseMode {
+    Repel,
+    Attract,
+}
+
+#[derive(Resource, Deref)]
+pub struct BubbleMouse(pub BubbleMouseMode);
+
 #[derive(Component)]
 pub struct Bubble {
     target: Vec2,
     timer: Timer,
+    /// Seconds since startup when the bubble appeared.
+    born: f32,
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -53,6 +124,8 @@ fn create_bubble(
     rng: &mut BubbleRng,
     sprite: &BubbleSprite,
     region: &Rectangle,
+    translation: Vec2,
+    born: f32,
 ) -> (
     Bubble,
     Sprite,
@@ -61,11 +134,13 @@ fn create_bubble(
     Collider,
     LockedAxes,
     Friction,
+    ExternalImpulse,
 ) {
     (
         Bubble {
             target: region.sample_interior(&mut rng.0),
             timer: Timer::from_seconds(3., TimerMode::Repeating),
+            born,
         },
         Sprite {
             image: (**sprite).clone(),
@@ -73,11 +148,12 @@ fn create_bubble(
             custom_size: Some(Vec2::splat(BUBBLE_RADIUS * 2.)),
             ..default()
         },
-        Transform::from_translation(region.sample_interior(&mut rng.0).extend(0.)),
+        Transform::from_translation(translation.extend(0.)),
         RigidBody::Dynamic,
         Collider::circle(BUBBLE_RADIUS as Scalar),
         LockedAxes::ROTATION_LOCKED,
         Friction::new(0.0),
+        ExternalImpulse::default(),
     )
 }
 
@@ -114,17 +190,20 @@ fn bubbles_spawn_system(
     mut rng: ResMut<BubbleRng>,
     sprite: Res<BubbleSprite>,
     visible_region: Res<BubbleVisibleRegion>,
-    spawn_amount: ResMut<BubbleAmount>,
+    spawn_amount: Res<BubbleAmount>,
+    bubbles: Query<(), With<Bubble>>,
     mut timer: Local<BubbleTimer>,
-    mut count: Local<u32>,
 ) {
     timer.tick(time.delta());
-    if timer.finished() && *count < **spawn_amount {
-        *count += 1;
+    if timer.finished() && (bubbles.iter().count() as u32) < **spawn_amount {
+        let region = Rectangle::from_size(**visible_region - BUBBLE_RADIUS * 2.);
+        let translation = region.sample_interior(&mut rng.0);
         commands.spawn(create_bubble(
             &mut rng,
             &sprite,
-            &Rectangle::from_size(**visible_region - BUBBLE_RADIUS * 2.),
+            &region,
+            translation,
+            time.elapsed_secs(),
         ));
     }
 }
@@ -181,3 +260,103 @@ fn bubble_color_system(time: Res<Time>, mut bubbles: Query<&mut Sprite, With<Bub
         sprite.color.set_hue(new_hue);
     }
 }
+
+fn bubble_mouse_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut events: EventReader<MouseEvent>,
+    mut bubbles: Query<(Entity, &Bubble, &Transform, &mut ExternalImpulse)>,
+    mouse: Res<BubbleMouse>,
+    spawn_amount: Res<BubbleAmount>,
+    visible_region: Res<BubbleVisibleRegion>,
+    sprite: Res<BubbleSprite>,
+    mut rng: ResMut<BubbleRng>,
+    mut cursor: Local<Option<Vec2>>,
+) {
+    let mut count = bubbles.iter().count() as u32;
+    let mut replaced = Vec::new();
+
+    for event in events.read() {
+        let position = cell_to_world(event.column, event.row, **visible_region);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                // Clicked bubbles count towards the same limit, so at the limit they replace the
+                // oldest bubble instead of adding to the pile.
+                if count < **spawn_amount {
+                    count += 1;
+                } else if let Some((oldest, ..)) = bubbles
+                    .iter()
+                    .filter(|(entity, ..)| !replaced.contains(entity))
+                    .min_by(|(_, a, ..), (_, b, ..)| a.born.total_cmp(&b.born))
+                {
+                    commands.entity(oldest).despawn();
+                    replaced.push(oldest);
+                }
+
+                let region = Rectangle::from_size(**visible_region - BUBBLE_RADIUS * 2.);
+                commands.spawn(create_bubble(
+                    &mut rng,
+                    &sprite,
+                    &region,
+                    position,
+                    time.elapsed_secs(),
+                ));
+            }
+            MouseEventKind::Moved | MouseEventKind::Drag(_) => *cursor = Some(position),
+            _ => {}
+        }
+    }
+
+    let Some(cursor) = *cursor else {
+        return;
+    };
+
+    for (_, _, transform, mut impulse) in &mut bubbles {
+        let offset = transform.translation.xy() - cursor;
+        let distance = offset.length();
+        if distance <= 0. || distance > BUBBLE_PUSH_RADIUS {
+            continue;
+        }
+
+        let falloff = 1. - distance / BUBBLE_PUSH_RADIUS;
+        let direction = match **mouse {
+            BubbleMouseMode::Repel => offset / distance,
+            BubbleMouseMode::Attract => -offset / distance,
+        };
+        impulse.apply_impulse(direction * BUBBLE_PUSH_STRENGTH * falloff);
+    }
+}
+
+/// Maps a terminal cell to the world position at its center. Each cell is rendered as 2x4 pixels,
+/// which the orthographic projection then scales by `ORTHO_SCALING` around the origin.
+fn cell_to_world(column: u16, row: u16, visible_region: Vec2) -> Vec2 {
+    let pixel = Vec2::new((column as f32 + 0.5) * 2., (row as f32 + 0.5) * 4.);
+    let world = pixel * ORTHO_SCALING;
+    Vec2::new(
+        world.x - visible_region.x / 2.,
+        visible_region.y / 2. - world.y,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What the camera shows of an 80x24 terminal.
+    const VISIBLE_REGION: Vec2 = Vec2::new(80., 48.);
+
+    #[test]
+    fn corner_cells_map_to_the_corners_of_the_world() {
+        assert_eq!(cell_to_world(0, 0, VISIBLE_REGION), Vec2::new(-39.5, 23.));
+        assert_eq!(cell_to_world(79, 23, VISIBLE_REGION), Vec2::new(39.5, -23.));
+    }
+
+    #[test]
+    fn cells_either_side_of_the_middle_are_mirrored() {
+        let left = cell_to_world(39, 11, VISIBLE_REGION);
+        let right = cell_to_world(40, 12, VISIBLE_REGION);
+
+        assert_eq!(left, -right);
+        assert_eq!(right - left, Vec2::new(1., -2.));
+    }
+}
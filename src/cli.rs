@@ -0,0 +1,339 @@
+use std::{any::TypeId, env, fs, path::PathBuf, process::ExitCode};
+
+use bevy::{
+    app::{App, AppExit},
+    color::Srgba,
+};
+use clap::{Arg, CommandFactory, FromArgMatches};
+
+use crate::{
+    args::{Args, Command},
+    config::Config,
+    doctor, list, terminal, AppPlugin, ExitMessage, ScreensaverRegistry, Settings,
+};
+
+/// Runs the `ttysvr` command line with the screensavers in `registry`, so that a binary with its
+/// own choice of screensavers gets the same options, subcommands and config file.
+///
+/// ```no_run
+/// use std::process::ExitCode;
+///
+/// use ttysvr::{MatrixSettings, ScreensaverRegistry, StarfieldSettings};
+///
+/// fn main() -> ExitCode {
+///     let mut registry = ScreensaverRegistry::empty();
+///     registry
+///         .register(StarfieldSettings::default())
+///         .register(MatrixSettings::default());
+///     ttysvr::run(registry)
+/// }
+/// ```
+pub fn run(mut registry: ScreensaverRegistry) -> ExitCode {
+    let mut cli = registry.command(Args::command());
+    let matches = match cli.try_get_matches_from_mut(env::args_os()) {
+        Ok(matches) => matches,
+        Err(error) => return report(error),
+    };
+    let Args {
+        background,
+        init,
+        cancel,
+        debug,
+        random_from,
+        exclude,
+        no_repeat,
+        command,
+    } = match Args::from_arg_matches(&matches) {
+        Ok(args) => args,
+        Err(error) => return report(error),
+    };
+
+    match command {
+        Some(Command::List { json }) => {
+            list::print_list(&registry, json);
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Doctor) => return doctor::doctor(),
+        None => {}
+    }
+
+    if let Some(delay) = init {
+        let executable_string = env::args().next().unwrap_or("ttysvr".into());
+        let args_string = rerun_args(&cli, env::args().skip(1));
+
+        #[rustfmt::skip]
+        println!(
+"
+TMOUT={delay}; trap \"{executable_string}{args_string}; zle reset-prompt\" ALRM
+
+# WRAP THIS COMMAND IN EVAL WITH BACKTICKS (ZSH ONLY)
+# EXAMPLE: eval `ttysvr{args_string} --init {delay}`
+"
+        );
+        return ExitCode::SUCCESS;
+    };
+
+    if cancel {
+        #[rustfmt::skip]
+        println!(
+"
+TMOUT=0
+
+# WRAP THIS COMMAND IN EVAL WITH BACKTICKS (ZSH ONLY)
+# EXAMPLE: eval `ttysvr --cancel`
+"
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(error) => return report(error),
+    };
+    for (choice, weight) in config.weights {
+        registry.weight(choice, weight);
+    }
+    for choice in config.random_from.into_iter().chain(random_from) {
+        registry.random_from(choice);
+    }
+    for choice in config.exclude.into_iter().chain(exclude) {
+        registry.exclude(choice);
+    }
+    registry.no_repeat(config.no_repeat || no_repeat);
+
+    let screensaver = match registry.parse(&matches, &mut rand::thread_rng()) {
+        Ok(screensaver) => screensaver,
+        Err(error) => return report(error),
+    };
+
+    let settings = Settings {
+        screensaver,
+        background: background.map_or(Srgba::NONE, |bg| bg.0),
+        debug,
+    };
+
+    terminal::restore_on_exit();
+
+    let message = ExitMessage::default();
+    let exit = App::new()
+        .add_plugins(AppPlugin(settings))
+        .insert_resource(message.clone())
+        .run();
+
+    if let Some(message) = message.lock().ok().and_then(|mut message| message.take()) {
+        eprintln!("{message}");
+    }
+
+    match exit {
+        AppExit::Success => ExitCode::SUCCESS,
+        AppExit::Error(code) => ExitCode::from(code.get()),
+    }
+}
+
+/// Prints `error` the way clap would before exiting, returning the exit code instead.
+fn report(error: clap::Error) -> ExitCode {
+    let _ = error.print();
+    ExitCode::from(error.exit_code() as u8)
+}
+
+/// Arguments for running the same screensaver again later, from the ones given in `args`, leaving
+/// out `--init` and making the values of options that take files absolute, since the shell may
+/// have changed directory by then. Options are looked up in `command`, so that flags and
+/// screensaver names are never mistaken for option values.
+fn rerun_args(command: &clap::Command, args: impl IntoIterator<Item = String>) -> String {
+    let mut args = args.into_iter();
+    let mut rerun = vec![];
+    // The command and the screensaver and subvariant chosen so far, whose options can follow.
+    let mut scope = vec![command];
+
+    while let Some(arg) = args.next() {
+        if arg == "--init" || arg == "-i" {
+            args.next();
+            continue;
+        }
+        if arg.starts_with("--init=") || arg.strip_prefix("-i").is_some_and(is_number) {
+            continue;
+        }
+        if arg == "--" {
+            rerun.push(arg);
+            rerun.extend(args.by_ref());
+            break;
+        }
+
+        let Some((name, value)) = split_option(&arg) else {
+            if let Some(subcommand) = scope.last().and_then(|last| last.find_subcommand(&arg)) {
+                scope.push(subcommand);
+            }
+            rerun.push(arg);
+            continue;
+        };
+
+        // Innermost first, since global options are only declared on the top level command.
+        let option = scope.iter().rev().find_map(|command| {
+            command
+                .get_arguments()
+                .find(|option| has_name(option, name))
+        });
+        let takes_value = option.is_some_and(|option| option.get_action().takes_values());
+        let takes_file = takes_value && option.is_some_and(is_file_option);
+
+        match value {
+            Some(value) if takes_file => {
+                let option = &arg[..arg.len() - value.len()];
+                rerun.push(format!("{option}{}", absolute_file(value)));
+            }
+            Some(_) => rerun.push(arg),
+            None => {
+                rerun.push(arg);
+                if let Some(value) = takes_value.then(|| args.next()).flatten() {
+                    rerun.push(if takes_file {
+                        absolute_file(&value)
+                    } else {
+                        value
+                    });
+                }
+            }
+        }
+    }
+
+    rerun
+        .iter()
+        .map(|arg| format!(" {}", shell_quote(arg)))
+        .collect()
+}
+
+/// Splits an option like `--sheet=toaster.png` or `-bFF0000` into its name and the value given
+/// along with it, if any. Returns `None` for arguments that aren't options.
+fn split_option(arg: &str) -> Option<(&str, Option<&str>)> {
+    if arg.starts_with("--") {
+        return Some(match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg, None),
+        });
+    }
+
+    let short = arg.strip_prefix('-')?.chars().next()?;
+    let (name, value) = arg.split_at(1 + short.len_utf8());
+    let value = value.strip_prefix('=').unwrap_or(value);
+    Some((name, Some(value).filter(|value| !value.is_empty())))
+}
+
+fn has_name(option: &Arg, name: &str) -> bool {
+    match name.strip_prefix("--") {
+        Some(long) => option.get_long() == Some(long),
+        None => option
+            .get_short()
+            .is_some_and(|short| name[1..].starts_with(short)),
+    }
+}
+
+/// Whether the values of `option` are paths, going by the type its value parser produces.
+fn is_file_option(option: &Arg) -> bool {
+    option.get_value_parser().type_id() == TypeId::of::<PathBuf>()
+}
+
+fn is_number(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|character| character.is_ascii_digit())
+}
+
+fn absolute_file(value: &str) -> String {
+    fs::canonicalize(value)
+        .ok()
+        .filter(|path| path.is_file())
+        .map_or_else(|| value.into(), |path| path.display().to_string())
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain =
+        |character: char| character.is_ascii_alphanumeric() || "-_=.,/:@%+".contains(character);
+
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.into()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rerun(args: &[&str]) -> String {
+        let cli = ScreensaverRegistry::default().command(Args::command());
+        rerun_args(&cli, args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn absolute(file: &str) -> String {
+        fs::canonicalize(file).unwrap().display().to_string()
+    }
+
+    #[test]
+    fn init_is_left_out() {
+        assert_eq!(rerun(&["--init", "60", "maze"]), " maze");
+        assert_eq!(rerun(&["maze", "--init=60"]), " maze");
+        assert_eq!(rerun(&["-i", "60", "maze"]), " maze");
+        assert_eq!(rerun(&["-i60", "--debug", "maze"]), " --debug maze");
+    }
+
+    #[test]
+    fn file_options_are_made_absolute() {
+        let sheet = absolute("Cargo.toml");
+
+        assert_eq!(
+            rerun(&["toasters", "--sheet", "Cargo.toml"]),
+            format!(" toasters --sheet {}", shell_quote(&sheet))
+        );
+        assert_eq!(
+            rerun(&["toasters", "--sheet=Cargo.toml", "--frames", "2"]),
+            format!(
+                " toasters {} --frames 2",
+                shell_quote(&format!("--sheet={sheet}"))
+            )
+        );
+    }
+
+    #[test]
+    fn other_options_keep_their_values() {
+        // A value naming a file is only made absolute for options that take files.
+        assert_eq!(
+            rerun(&["-b", "Cargo.toml", "--random-from=Cargo.toml"]),
+            " -b Cargo.toml --random-from=Cargo.toml"
+        );
+        // Flags don't take the next argument as their value.
+        assert_eq!(
+            rerun(&["--debug", "Cargo.toml", "maze"]),
+            " --debug Cargo.toml maze"
+        );
+    }
+
+    #[test]
+    fn arguments_after_a_double_dash_are_kept_as_they_are() {
+        assert_eq!(
+            rerun(&["toasters", "--", "--sheet", "Cargo.toml", "--init", "60"]),
+            " toasters -- --sheet Cargo.toml --init 60"
+        );
+    }
+
+    #[test]
+    fn split_option_separates_values() {
+        assert_eq!(split_option("--sheet"), Some(("--sheet", None)));
+        assert_eq!(
+            split_option("--sheet=a.png"),
+            Some(("--sheet", Some("a.png")))
+        );
+        assert_eq!(split_option("-b"), Some(("-b", None)));
+        assert_eq!(split_option("-bFF0000"), Some(("-b", Some("FF0000"))));
+        assert_eq!(split_option("-b=FF0000"), Some(("-b", Some("FF0000"))));
+        assert_eq!(split_option("maze"), None);
+        assert_eq!(split_option("-"), None);
+    }
+
+    #[test]
+    fn shell_quote_escapes_what_the_shell_would_expand() {
+        assert_eq!(shell_quote("--sheet=/tmp/a.png"), "--sheet=/tmp/a.png");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my sheet.png"), "'my sheet.png'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        assert_eq!(shell_quote("it's.png"), "'it'\\''s.png'");
+    }
+}
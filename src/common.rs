@@ -1,39 +1,187 @@
 use std::io;
 
 use bevy::utils::error;
-use bevy::{diagnostic::DiagnosticsStore, prelude::*};
-use bevy_ratatui::event::{KeyEvent, MouseEvent};
+use bevy::{
+    diagnostic::{
+        DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+use bevy_ratatui::event::{KeyEvent, MouseEvent, ResizeEvent};
 use bevy_ratatui::terminal::RatatuiContext;
 use bevy_ratatui_camera::RatatuiCameraWidget;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Rect, Size};
+use ratatui::style::Color as TerminalColor;
+use ratatui::text::Line;
+use ratatui::widgets::{Clear, Paragraph, Widget};
 
 use crate::Flags;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        Update,
-        (
-            draw_scene_system.map(error),
-            handle_keyboard_system,
-            handle_mouse_system,
-        ),
-    );
+    app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+        .init_resource::<Viewport>()
+        .add_systems(
+            Update,
+            (
+                viewport_system,
+                handle_keyboard_system,
+                handle_mouse_system.run_if(not(resource_exists::<MouseInteraction>)),
+            ),
+        );
 }
 
+/// Draws straight to the terminal, for when the screensaver has the terminal to itself instead of
+/// being drawn by a [`ScreensaverWidget`](crate::ScreensaverWidget).
+pub(super) fn terminal_plugin(app: &mut App) {
+    app.add_systems(Startup, terminal_viewport_system)
+        .add_systems(Update, draw_scene_system.map(error));
+}
+
+/// Size of the area the screensaver is drawn into, in terminal cells.
+#[derive(Resource, Deref, DerefMut, Default, Clone, Copy, PartialEq)]
+pub struct Viewport(pub Size);
+
+/// Text drawn on top of the camera output, anchored to one corner of the terminal.
+#[derive(Component)]
+pub struct Overlay {
+    pub lines: Vec<Line<'static>>,
+    pub anchor: OverlayAnchor,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    Center,
+}
+
+/// Single characters drawn on top of the camera output, below any overlays. Cells without a glyph
+/// keep showing the camera output.
+#[derive(Component, Default)]
+pub struct Glyphs(pub Vec<Glyph>);
+
+pub struct Glyph {
+    /// Column and row, counted from the top left of the screensaver.
+    pub position: (u16, u16),
+    pub symbol: char,
+    pub color: TerminalColor,
+}
+
+/// When present, mouse events are left to the variant instead of exiting the screensaver.
+#[derive(Resource, Default)]
+pub struct MouseInteraction;
+
 fn draw_scene_system(
     mut ratatui: ResMut<RatatuiContext>,
     widget: Query<&RatatuiCameraWidget>,
-    _flags: Res<Flags>,
-    _diagnostics: Res<DiagnosticsStore>,
+    glyphs: Query<&Glyphs>,
+    overlays: Query<&Overlay>,
+    flags: Res<Flags>,
+    diagnostics: Res<DiagnosticsStore>,
 ) -> io::Result<()> {
+    let stats = flags.debug.then(|| debug_overlay(&diagnostics));
+
     ratatui.draw(|frame| {
-        if let Ok(widget) = widget.get_single() {
-            frame.render_widget(widget, frame.area());
-        }
+        render_scene(
+            widget.get_single().ok(),
+            glyphs.iter(),
+            overlays.iter().chain(&stats),
+            frame.area(),
+            frame.buffer_mut(),
+        );
     })?;
 
     Ok(())
 }
 
+/// Draws the camera output with the glyphs and then the overlays on top of it.
+pub(super) fn render_scene<'a>(
+    widget: Option<&RatatuiCameraWidget>,
+    glyphs: impl Iterator<Item = &'a Glyphs>,
+    overlays: impl Iterator<Item = &'a Overlay>,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    if let Some(widget) = widget {
+        widget.render(area, buf);
+    }
+
+    for glyph in glyphs.flat_map(|glyphs| &glyphs.0) {
+        let (x, y) = glyph.position;
+        if x < area.width && y < area.height {
+            if let Some(cell) = buf.cell_mut((area.x + x, area.y + y)) {
+                cell.set_char(glyph.symbol).set_fg(glyph.color);
+            }
+        }
+    }
+
+    for overlay in overlays {
+        let overlay_area = overlay_area(overlay, area);
+        Clear.render(overlay_area, buf);
+        Paragraph::new(overlay.lines.clone()).render(overlay_area, buf);
+    }
+}
+
+fn terminal_viewport_system(ratatui: Res<RatatuiContext>, mut viewport: ResMut<Viewport>) {
+    if let Ok(size) = ratatui.size() {
+        **viewport = size;
+    }
+}
+
+fn viewport_system(mut resize_events: EventReader<ResizeEvent>, mut viewport: ResMut<Viewport>) {
+    for resize in resize_events.read() {
+        **viewport = Size::new(resize.width, resize.height);
+    }
+}
+
+/// Frame rate and number of entities, for keeping an eye on performance with `--debug`.
+pub(super) fn debug_overlay(diagnostics: &DiagnosticsStore) -> Overlay {
+    let smoothed = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.)
+    };
+
+    Overlay {
+        lines: vec![
+            format!("fps: {:.0}", smoothed(&FrameTimeDiagnosticsPlugin::FPS)).into(),
+            format!(
+                "entities: {:.0}",
+                smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+            )
+            .into(),
+        ],
+        anchor: OverlayAnchor::TopLeft,
+    }
+}
+
+fn overlay_area(overlay: &Overlay, area: Rect) -> Rect {
+    let width = overlay
+        .lines
+        .iter()
+        .map(Line::width)
+        .max()
+        .unwrap_or(0)
+        .min(area.width as usize) as u16;
+    let height = overlay.lines.len().min(area.height as usize) as u16;
+
+    let x = match overlay.anchor {
+        OverlayAnchor::TopLeft | OverlayAnchor::BottomLeft => area.x,
+        OverlayAnchor::TopRight => area.right() - width,
+        OverlayAnchor::Center => area.x + (area.width - width) / 2,
+    };
+    let y = match overlay.anchor {
+        OverlayAnchor::TopLeft | OverlayAnchor::TopRight => area.y,
+        OverlayAnchor::BottomLeft => area.bottom() - height,
+        OverlayAnchor::Center => area.y + (area.height - height) / 2,
+    };
+
+    Rect::new(x, y, width, height)
+}
+
 fn handle_keyboard_system(
     mut ratatui_events: EventReader<KeyEvent>,
     mut exit: EventWriter<AppExit>,
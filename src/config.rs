@@ -0,0 +1,151 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::error::ErrorKind;
+use serde::Deserialize;
+
+/// Settings read from `config.toml`, in `$XDG_CONFIG_HOME/ttysvr` (or `~/.config/ttysvr`), for
+/// choosing a screensaver at random.
+///
+/// ```toml
+/// random-from = ["logo", "maze"]
+/// exclude = ["maze:brick"]
+/// no-repeat = true
+///
+/// [weights]
+/// maze = 3
+/// "logo:tty" = 0.5
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub random_from: Vec<String>,
+    pub exclude: Vec<String>,
+    pub no_repeat: bool,
+    pub weights: BTreeMap<String, f64>,
+}
+
+impl Config {
+    /// Reads the config file, or the defaults if there isn't one.
+    pub fn load() -> Result<Self, clap::Error> {
+        Self::read().map_err(|error| clap::Error::raw(ErrorKind::Io, format!("{error}\n")))
+    }
+
+    /// Like [`Config::load`], with errors as plain messages naming the file.
+    pub fn read() -> Result<Self, String> {
+        let Some(path) = config_path() else {
+            return Ok(Self::default());
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(invalid_config(&path, error)),
+        };
+
+        Self::parse(&contents).map_err(|error| invalid_config(&path, error))
+    }
+
+    /// Parses the contents of a config file.
+    fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(contents).map_err(|error| error.to_string())?;
+
+        // Caught here rather than when picking at random, so that the message can name the entry.
+        if let Some((choice, weight)) = config
+            .weights
+            .iter()
+            .find(|(_, weight)| !weight.is_finite() || **weight <= 0.)
+        {
+            return Err(format!(
+                "weight for {choice} must be a positive number, got {weight}"
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+fn invalid_config(path: &Path, error: impl Display) -> String {
+    format!("invalid config {}: {error}", path.display())
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Directory for the config file, following the XDG base directory spec where it applies.
+fn config_dir() -> Option<PathBuf> {
+    if let Some(config_home) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(config_home).join("ttysvr"));
+    }
+
+    if let Some(app_data) = env::var_os("APPDATA").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(app_data).join("ttysvr"));
+    }
+
+    env::var_os("HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(|home| PathBuf::from(home).join(".config/ttysvr"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_setting() {
+        let config = Config::parse(
+            r#"
+            random-from = ["logo", "maze"]
+            exclude = ["maze:brick"]
+            no-repeat = true
+
+            [weights]
+            maze = 3
+            "logo:tty" = 0.5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.random_from, ["logo", "maze"]);
+        assert_eq!(config.exclude, ["maze:brick"]);
+        assert!(config.no_repeat);
+        assert_eq!(
+            config.weights,
+            BTreeMap::from([("logo:tty".into(), 0.5), ("maze".into(), 3.)])
+        );
+    }
+
+    #[test]
+    fn settings_left_out_are_defaults() {
+        let config = Config::parse("").unwrap();
+
+        assert!(config.random_from.is_empty());
+        assert!(config.exclude.is_empty());
+        assert!(!config.no_repeat);
+        assert!(config.weights.is_empty());
+    }
+
+    #[test]
+    fn weights_have_to_be_positive_numbers() {
+        for (weight, shown) in [("0", "0"), ("-1", "-1"), ("nan", "NaN"), ("inf", "inf")] {
+            assert_eq!(
+                Config::parse(&format!("[weights]\nmaze = {weight}")).err(),
+                Some(format!(
+                    "weight for maze must be a positive number, got {shown}"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = Config::parse("no-repeats = true").err().unwrap();
+        assert!(error.contains("unknown field `no-repeats`"), "{error}");
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    panic,
+    process::ExitCode,
+};
+
+use bevy::{
+    app::PluginsState, log::LogPlugin, prelude::*, render::renderer::RenderAdapterInfo,
+    tasks::tick_global_task_pools_on_main_thread, winit::WinitPlugin,
+};
+use ratatui::crossterm::terminal;
+
+use crate::config::{config_path, Config};
+
+/// Smallest terminal, in characters, that the screensavers still look like something in.
+const MIN_TERMINAL_SIZE: (u16, u16) = (40, 12);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Outcome of one check, with a suggested fix when it didn't pass.
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warning,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Error,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Checks whether the screensavers can run here, prints what was found along with fixes for
+/// anything that's wrong, and fails if any of it would stop them from running.
+pub fn doctor() -> ExitCode {
+    let mut checks = vec![
+        check_terminal(),
+        check_raw_mode(),
+        check_size(),
+        check_color(),
+    ];
+    checks.extend(check_render_strategies());
+    checks.extend([check_renderer(), check_shell(), check_config()]);
+
+    for check in &checks {
+        let status = match check.status {
+            Status::Ok => "ok",
+            Status::Warning => "warning",
+            Status::Error => "error",
+        };
+        println!("{status:>7}  {}: {}", check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("         fix: {fix}");
+        }
+    }
+
+    if checks.iter().any(|check| check.status == Status::Error) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check_terminal() -> Check {
+    const NAME: &str = "terminal";
+
+    match (io::stdin().is_terminal(), io::stdout().is_terminal()) {
+        (true, true) => Check::ok(NAME, "input and output are a terminal"),
+        (false, _) => Check::error(
+            NAME,
+            "input isn't a terminal, so key presses can't stop the screensaver",
+            "run ttysvr straight from an interactive shell, without redirecting its input",
+        ),
+        (_, false) => Check::error(
+            NAME,
+            "output isn't a terminal, so there is nothing to draw on",
+            "run ttysvr straight from an interactive shell, without piping its output",
+        ),
+    }
+}
+
+fn check_raw_mode() -> Check {
+    const NAME: &str = "raw mode";
+
+    match terminal::enable_raw_mode().and_then(|_| terminal::disable_raw_mode()) {
+        Ok(()) => Check::ok(NAME, "can be switched on and back off"),
+        Err(error) => Check::error(
+            NAME,
+            format!("can't be switched on: {error}"),
+            "use a terminal emulator or ssh session with a pty (ssh -t), rather than a plain pipe",
+        ),
+    }
+}
+
+fn check_size() -> Check {
+    const NAME: &str = "size";
+
+    match terminal::size() {
+        Ok((width, height)) if width < MIN_TERMINAL_SIZE.0 || height < MIN_TERMINAL_SIZE.1 => {
+            Check::warning(
+                NAME,
+                format!("{width}x{height} is too small to make much out"),
+                format!(
+                    "make the terminal at least {}x{}",
+                    MIN_TERMINAL_SIZE.0, MIN_TERMINAL_SIZE.1
+                ),
+            )
+        }
+        Ok((width, height)) => Check::ok(NAME, format!("{width}x{height}")),
+        Err(error) => Check::error(
+            NAME,
+            format!("unknown: {error}"),
+            "run ttysvr in a terminal that reports its size",
+        ),
+    }
+}
+
+fn check_color() -> Check {
+    const NAME: &str = "color";
+
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if term_program == "Apple_Terminal" {
+        return Check::warning(
+            NAME,
+            "macOS Terminal doesn't support 24-bit color, so colors will be wrong",
+            "use a terminal with 24-bit color, like iTerm2, Kitty, WezTerm or Alacritty",
+        );
+    }
+
+    if truecolor() {
+        return Check::ok(NAME, "24-bit color");
+    }
+
+    Check::warning(
+        NAME,
+        format!("no sign of 24-bit color (TERM={term}, COLORTERM={colorterm})"),
+        "if the terminal supports 24-bit color, export COLORTERM=truecolor, otherwise switch to \
+         one that does",
+    )
+}
+
+/// Whether the terminal claims 24-bit color, which macOS Terminal does without supporting it.
+fn truecolor() -> bool {
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+    let apple_terminal = env::var("TERM_PROGRAM").is_ok_and(|program| program == "Apple_Terminal");
+
+    !apple_terminal
+        && (matches!(colorterm.as_str(), "truecolor" | "24bit") || term.ends_with("-direct"))
+}
+
+/// Each way that bevy_ratatui_camera can draw a rendered image with characters, and whether this
+/// terminal can show it.
+fn check_render_strategies() -> Vec<Check> {
+    const UTF8_FIX: &str = "use a UTF-8 locale, for example export LANG=en_US.UTF-8";
+
+    // The first of these that is set is the one that applies.
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_default();
+    let unicode = locale.to_uppercase().replace('-', "").contains("UTF8");
+    let colors = if truecolor() {
+        "24-bit color"
+    } else {
+        "approximate colors"
+    };
+
+    let half_blocks = if unicode {
+        Check::ok(
+            "strategy: half blocks",
+            format!("each character draws two pixels in {colors}"),
+        )
+    } else {
+        Check::warning(
+            "strategy: half blocks",
+            format!("needs unicode, but the locale is '{locale}'"),
+            UTF8_FIX,
+        )
+    };
+
+    let edge_detection = if unicode {
+        Check::ok(
+            "strategy: edge detection",
+            "line characters follow the edges of shapes",
+        )
+    } else {
+        Check::warning(
+            "strategy: edge detection",
+            format!("its line characters need unicode, but the locale is '{locale}'"),
+            UTF8_FIX,
+        )
+    };
+
+    vec![
+        half_blocks,
+        Check::ok(
+            "strategy: luminance",
+            format!("ASCII characters stand in for brightness, in {colors}"),
+        ),
+        edge_detection,
+    ]
+}
+
+fn check_renderer() -> Check {
+    const NAME: &str = "renderer";
+
+    // Starting bevy's renderer is the surest way to know that it will start, and it panics if no
+    // GPU or graphics drivers can be found.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(|| {
+        let mut app = App::new();
+        app.add_plugins(
+            DefaultPlugins
+                .build()
+                .disable::<WinitPlugin>()
+                .disable::<LogPlugin>(),
+        );
+
+        while app.plugins_state() == PluginsState::Adding {
+            tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        app.world()
+            .get_resource::<RenderAdapterInfo>()
+            .map(|info| format!("{} ({:?})", info.name, info.backend))
+    });
+    panic::set_hook(hook);
+
+    match result {
+        Ok(Some(adapter)) => Check::ok(NAME, adapter),
+        Ok(None) => Check::error(
+            NAME,
+            "no graphics adapter was set up",
+            "check the graphics drivers, see docs/linux_dependencies.md in the bevy repository",
+        ),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "failed to start".into());
+
+            Check::error(
+                NAME,
+                message,
+                "install the graphics drivers and libraries listed in docs/linux_dependencies.md \
+                 in the bevy repository (Vulkan or OpenGL drivers, such as mesa)",
+            )
+        }
+    }
+}
+
+fn check_shell() -> Check {
+    const NAME: &str = "shell";
+
+    let shell = env::var("SHELL").unwrap_or_default();
+    if !shell.ends_with("zsh") {
+        return Check::warning(
+            NAME,
+            format!("starting after a delay with --init only works in zsh, not '{shell}'"),
+            "run ttysvr from zsh to use --init, running it straight away works in any shell",
+        );
+    }
+
+    // TMOUT is a shell variable that isn't usually exported, so not seeing it says nothing about
+    // whether the idle trap is installed.
+    match env::var("TMOUT").ok().filter(|delay| !delay.is_empty()) {
+        Some(delay) if delay != "0" => Check::ok(NAME, format!("zsh, TMOUT is {delay} seconds")),
+        _ => Check::ok(
+            NAME,
+            "zsh, could not determine whether the idle trap is installed (check with \
+             `echo $TMOUT; trap`)",
+        ),
+    }
+}
+
+fn check_config() -> Check {
+    const NAME: &str = "config";
+
+    let Some(path) = config_path() else {
+        return Check::ok(NAME, "no config directory, using defaults");
+    };
+
+    match Config::read() {
+        Ok(_) if path.exists() => Check::ok(NAME, format!("{} is valid", path.display())),
+        Ok(_) => Check::ok(NAME, format!("none at {}, using defaults", path.display())),
+        Err(error) => Check::error(NAME, error, "fix or remove the config file"),
+    }
+}
@@ -1,89 +1,115 @@
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+pub use assets::file_asset_path;
 use bevy::prelude::*;
 use bevy::winit::WinitPlugin;
 use bevy::{app::ScheduleRunnerPlugin, log::LogPlugin};
 use bevy_ratatui::RatatuiPlugins;
 use bevy_ratatui_camera::RatatuiCameraPlugin;
-use logo::LogoPath;
-pub use logo::{LOGO_PATH_DVD, LOGO_PATH_TTY};
-use maze::MazePaths;
+pub use bubbles::{BubbleMouseMode, BubblesSettings};
+pub use cli::run;
+pub use logo::{LogoSettings, LOGO_PATH_DVD, LOGO_PATH_TTY};
+pub use matrix::{MatrixCharset, MatrixSettings};
 pub use maze::{
+    MazeAlgorithm, MazeLayout, MazeLighting, MazeObject, MazeSettings, MazeWalker,
     MAZE_CEILING_PATH_BRICK, MAZE_CEILING_PATH_HEDGE, MAZE_WALL_PATH_BRICK, MAZE_WALL_PATH_HEDGE,
 };
-use rand::{distributions::Standard, prelude::Distribution, Rng};
+pub use pipes::PipesSettings;
+pub use screensaver::{Screensaver, ScreensaverRegistry};
+pub use starfield::{StarfieldSettings, StarfieldStyle};
+pub use toasters::{ToasterSheet, ToastersSettings, TOASTERS_PATH_TOAST, TOASTERS_PATH_TOASTER};
+pub use widget::ScreensaverWidget;
 
+mod args;
 mod assets;
 mod bubbles;
+mod cli;
 mod common;
+mod config;
+mod doctor;
+mod list;
 mod logo;
+mod matrix;
 mod maze;
+mod pipes;
+mod screensaver;
+mod starfield;
+mod state;
+mod terminal;
+mod toasters;
+mod widget;
 
+/// Runs the screensaver on its own, taking over the terminal until a key is pressed. Putting the
+/// terminal back after a panic or a signal is left to [`run`], since it takes over the whole
+/// process.
 pub struct AppPlugin(pub Settings);
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1. / 60.,
+        )));
+
+        build_screensaver(app, &self.0);
+
+        // The terminal only reports the mouse while it is captured, which is left off unless the
+        // screensaver reacts to it.
+        let enable_mouse_capture = app.world().contains_resource::<common::MouseInteraction>();
         app.add_plugins((
-            DefaultPlugins
-                .set(ImagePlugin::default_nearest())
-                .disable::<WinitPlugin>()
-                .disable::<LogPlugin>(),
-            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / 60.)),
-            RatatuiPlugins::default(),
-            RatatuiCameraPlugin,
-        ))
-        .init_resource::<Flags>();
-
-        app.add_plugins((assets::plugin, common::plugin));
-
-        let Settings {
-            ref variant,
-            ref background,
-        } = self.0;
-
-        app.insert_resource(ClearColor(Color::Srgba(*background)));
-
-        match variant {
-            SaverVariant::Logo(ref logo_path) => {
-                app.insert_resource(LogoPath(logo_path.into()));
-            }
-            SaverVariant::Maze(ref maze_wall, ref maze_ceiling) => {
-                app.insert_resource(MazePaths(maze_wall.into(), maze_ceiling.into()));
-            }
-            _ => {}
-        }
-
-        app.add_plugins(match variant {
-            SaverVariant::Bubbles => bubbles::plugin,
-            SaverVariant::Logo(_) => logo::plugin,
-            SaverVariant::Maze(_, _) => maze::plugin,
-        });
+            RatatuiPlugins {
+                enable_mouse_capture,
+                ..default()
+            },
+            common::terminal_plugin,
+        ));
     }
 }
 
+/// Everything the screensaver needs apart from the terminal, shared by [`AppPlugin`] and
+/// [`ScreensaverWidget`].
+fn build_screensaver(app: &mut App, settings: &Settings) {
+    assets::register_sources(app);
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(ImagePlugin::default_nearest())
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>(),
+        RatatuiCameraPlugin,
+    ));
+
+    let Settings {
+        ref screensaver,
+        ref background,
+        debug,
+    } = *settings;
+
+    app.insert_resource(Flags { debug, ..default() })
+        .init_resource::<ExitMessage>();
+
+    app.add_plugins((assets::plugin, common::plugin));
+
+    app.insert_resource(ClearColor(Color::Srgba(*background)));
+
+    screensaver.build(app);
+}
+
 #[derive(Resource, Default)]
 pub struct Flags {
-    _debug: bool,
+    debug: bool,
     _msgs: Vec<String>,
 }
 
+/// Why the screensaver stopped by itself, which [`run`] prints once the terminal is back to normal.
+#[derive(Resource, Clone, Default, Deref)]
+struct ExitMessage(Arc<Mutex<Option<String>>>);
+
 pub struct Settings {
-    pub variant: SaverVariant,
+    pub screensaver: Box<dyn Screensaver>,
     pub background: Srgba,
-}
-
-pub enum SaverVariant {
-    Bubbles,
-    Logo(String),
-    Maze(String, String),
-}
-
-impl Distribution<SaverVariant> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SaverVariant {
-        match rng.gen_range(0..=2) {
-            0 => SaverVariant::Bubbles,
-            1 => SaverVariant::Logo(LOGO_PATH_TTY.into()),
-            _ => SaverVariant::Maze(MAZE_WALL_PATH_BRICK.into(), MAZE_CEILING_PATH_BRICK.into()),
-        }
-    }
+    /// Show the frame rate and entity count in the corner.
+    pub debug: bool,
 }
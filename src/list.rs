@@ -0,0 +1,123 @@
+use clap::{builder::StyledStr, Arg, Command};
+use serde_json::{json, Value};
+
+use crate::ScreensaverRegistry;
+
+/// Prints the screensavers in `registry`, read from the subcommands they add to the command line
+/// so that the list always matches what can be run.
+pub fn print_list(registry: &ScreensaverRegistry, json: bool) {
+    let commands: Vec<Command> = registry.commands().collect();
+
+    if json {
+        let list: Vec<Value> = commands.iter().map(command_json).collect();
+        println!("{}", Value::Array(list));
+        return;
+    }
+
+    for command in &commands {
+        let subvariants: Vec<&str> = command.get_subcommands().map(Command::get_name).collect();
+        let name = match subvariants.as_slice() {
+            [] => command.get_name().to_string(),
+            _ => format!("{} [{}]", command.get_name(), subvariants.join("|")),
+        };
+        println!("{name}\n    {}", text(command.get_about()));
+
+        for arg in parameters(command) {
+            println!("    {}", usage(arg));
+            println!("        {}{}", text(arg.get_help()), details(arg));
+        }
+        println!();
+    }
+}
+
+fn command_json(command: &Command) -> Value {
+    json!({
+        "name": command.get_name(),
+        "description": text(command.get_about()),
+        "subvariants": command
+            .get_subcommands()
+            .map(Command::get_name)
+            .collect::<Vec<_>>(),
+        "parameters": parameters(command)
+            .map(|arg| json!({
+                "long": arg.get_long(),
+                "short": arg.get_short().map(String::from),
+                "value": value_name(arg),
+                "description": text(arg.get_help()),
+                "default": defaults(arg),
+                "possible_values": possible_values(arg),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn parameters(command: &Command) -> impl Iterator<Item = &Arg> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set() && arg.get_long().is_some())
+}
+
+/// How the option is written, like `-s, --size <WIDTHxHEIGHT>`.
+fn usage(arg: &Arg) -> String {
+    let short = arg
+        .get_short()
+        .map_or(String::new(), |short| format!("-{short}, "));
+    let long = arg.get_long().unwrap_or_default();
+
+    match value_name(arg) {
+        Some(value) => format!("{short}--{long} <{value}>"),
+        None => format!("{short}--{long}"),
+    }
+}
+
+fn details(arg: &Arg) -> String {
+    let mut details = String::new();
+    if let Some(default) = defaults(arg) {
+        details += &format!(" [default: {default}]");
+    }
+    if !possible_values(arg).is_empty() {
+        details += &format!(" [possible values: {}]", possible_values(arg).join(", "));
+    }
+    details
+}
+
+fn value_name(arg: &Arg) -> Option<String> {
+    if !arg.get_action().takes_values() {
+        return None;
+    }
+
+    Some(match arg.get_value_names() {
+        Some(names) => names
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => arg.get_id().to_string(),
+    })
+}
+
+fn defaults(arg: &Arg) -> Option<String> {
+    let defaults: Vec<_> = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy())
+        .collect();
+
+    (!defaults.is_empty()).then(|| defaults.join(","))
+}
+
+fn possible_values(arg: &Arg) -> Vec<String> {
+    if !arg.get_action().takes_values() {
+        return vec![];
+    }
+
+    arg.get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+fn text(text: Option<&StyledStr>) -> String {
+    text.map(StyledStr::to_string).unwrap_or_default()
+}
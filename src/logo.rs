@@ -1,8 +1,18 @@
+use bevy::asset::{AssetPath, LoadState};
 use bevy::prelude::*;
+use bevy::text::TextLayoutInfo;
 use bevy_ratatui::event::ResizeEvent;
 use bevy_ratatui_camera::RatatuiCamera;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::common::{Overlay, OverlayAnchor};
+use crate::state;
+use crate::ExitMessage;
+
+mod cli;
 
 pub const LOGO_PATH_DVD: &str = "embedded://ttysvr/../assets/dvd_logo.png";
 pub const LOGO_PATH_TTY: &str = "embedded://ttysvr/../assets/tty_logo.png";
@@ -11,21 +21,138 @@ const STARTING_DIMENSIONS: (u32, u32) = (256, 256);
 const ORTHO_SCALING: f32 = 0.5;
 const LOGO_RADIUS: f32 = 32.;
 const LOGO_SPEED: f32 = 24.;
+const LOGO_ALPHA_THRESHOLD: f32 = 0.1;
+const LOGO_FONT_SIZE: f32 = 24.;
+const LOGO_HUE_SHIFT: f32 = 68.;
+const CORNER_WINDOW: f32 = 0.1;
+const CORNER_AIM_SECONDS: f32 = 12.;
+/// Random positions tried for each logo when looking for a spot clear of the others.
+const PLACEMENT_ATTEMPTS: u32 = 64;
+const CORNER_HITS_STATE: &str = "corner_hits";
+const CELEBRATION_SECONDS: f32 = 4.;
+const FLASH_SECONDS: f32 = 0.6;
+const CONFETTI_AMOUNT: u32 = 48;
+const CONFETTI_SPEED: f32 = 48.;
+const CONFETTI_GRAVITY: f32 = 40.;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<LogoVisibleRegion>()
+        .init_resource::<CornerHits>()
+        .add_event::<CornerHitEvent>()
+        .insert_resource(LogoRng(ChaCha8Rng::seed_from_u64(19878367467712)))
         .add_systems(Startup, logo_setup_system)
-        .add_systems(Update, (handle_resize_system, logo_movement_system));
+        .add_systems(
+            Update,
+            (
+                handle_resize_system,
+                logo_load_failure_system,
+                (logo_measure_system, logo_text_measure_system),
+                logo_placement_system,
+                (
+                    logo_movement_system,
+                    logo_collision_system,
+                    logo_color_system,
+                )
+                    .chain(),
+                corner_celebration_system,
+                (flash_system, confetti_system, celebration_overlay_system),
+            )
+                .chain(),
+        );
 }
 
 #[derive(Component, Deref, DerefMut)]
 struct Logo {
     #[deref]
     velocity: Vec2,
+    /// Applied to the logo's sprite or text, and shifted in hue on every bounce.
+    color: Color,
+    /// Box around the visible pixels of the logo, relative to its center. Starts out as the whole
+    /// square sprite, and is measured from the image or text layout once it is available.
+    bounds: Rect,
+    measured: bool,
+    /// Logos stay hidden and still until they have been placed clear of the others.
+    placed: bool,
+    last_bounce_x: f32,
+    last_bounce_y: f32,
+}
+
+impl Logo {
+    fn new(velocity: Vec2, color: Color) -> Self {
+        Self {
+            velocity,
+            color,
+            bounds: Rect::from_center_half_size(Vec2::ZERO, Vec2::splat(LOGO_RADIUS)),
+            measured: false,
+            placed: false,
+            last_bounce_x: f32::NEG_INFINITY,
+            last_bounce_y: f32::NEG_INFINITY,
+        }
+    }
+}
+
+/// Marks the logo that is steered into a corner whenever the visible region changes.
+#[derive(Component)]
+struct CornerAim;
+
+#[derive(Event)]
+struct CornerHitEvent {
+    position: Vec2,
+}
+
+/// Total corner hits across runs, persisted to the state directory.
+#[derive(Resource, Deref, DerefMut)]
+struct CornerHits(u64);
+
+impl Default for CornerHits {
+    fn default() -> Self {
+        Self(
+            state::load(CORNER_HITS_STATE)
+                .and_then(|hits| hits.parse().ok())
+                .unwrap_or(0),
+        )
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct LogoRng(ChaCha8Rng);
+
+#[derive(Component, Deref, DerefMut)]
+struct Flash(Timer);
+
+#[derive(Component)]
+struct Confetti {
+    velocity: Vec2,
+    timer: Timer,
 }
 
-#[derive(Resource, Deref)]
-pub struct LogoPath(pub String);
+#[derive(Component, Deref, DerefMut)]
+struct CelebrationOverlay(Timer);
+
+#[derive(Resource, Clone)]
+pub struct LogoSettings {
+    /// Images assigned to the logos in turn, so that `count` logos cycle through this list.
+    pub images: Vec<AssetPath<'static>>,
+    pub count: u32,
+    /// Steer the first logo so that it is guaranteed to hit a corner.
+    pub corner: bool,
+    /// Bounce this text instead of the images.
+    pub text: Option<String>,
+    /// Font for `text`, falling back to bevy's bundled font.
+    pub font: Option<AssetPath<'static>>,
+}
+
+impl Default for LogoSettings {
+    fn default() -> Self {
+        Self {
+            images: vec![LOGO_PATH_DVD.into()],
+            count: 1,
+            corner: false,
+            text: None,
+            font: None,
+        }
+    }
+}
 
 #[derive(Resource, Deref, DerefMut, Default)]
 struct LogoVisibleRegion(Vec2);
@@ -34,7 +161,8 @@ fn logo_setup_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut visible_region: ResMut<LogoVisibleRegion>,
-    logo_path: Res<LogoPath>,
+    mut rng: ResMut<LogoRng>,
+    settings: Res<LogoSettings>,
 ) {
     commands.spawn((
         RatatuiCamera::autoresize().with_dimensions(STARTING_DIMENSIONS),
@@ -46,61 +174,467 @@ fn logo_setup_system(
     ));
 
     **visible_region = get_visible_region(STARTING_DIMENSIONS);
-    let image = asset_server.load(&**logo_path);
-    let mut rng = ChaCha8Rng::seed_from_u64(19878367467712);
-    let region = Rectangle::from_size(**visible_region * 0.5 - LOGO_RADIUS * 2.);
+    let images: Vec<Handle<Image>> = settings
+        .images
+        .iter()
+        .map(|path| asset_server.load(path))
+        .collect();
+    let font = settings
+        .font
+        .as_ref()
+        .map(|path| asset_server.load(path))
+        .unwrap_or_default();
 
-    commands.spawn((
-        Logo {
-            velocity: Vec2::new(LOGO_SPEED, -LOGO_SPEED),
-        },
-        Sprite {
-            image,
-            color: Color::hsl(0., 1., 0.6),
-            custom_size: Some(Vec2::splat(LOGO_RADIUS * 2.)),
-            ..default()
-        },
-        Transform::from_translation(region.sample_interior(&mut rng).extend(0.)),
-    ));
+    for (index, image) in (0..settings.count).zip(images.iter().cycle()) {
+        let velocity = if index == 0 {
+            Vec2::new(LOGO_SPEED, -LOGO_SPEED)
+        } else {
+            Vec2::new(
+                LOGO_SPEED * random_sign(&mut rng),
+                LOGO_SPEED * random_sign(&mut rng),
+            )
+        };
+
+        let color = Color::hsl(index as f32 * LOGO_HUE_SHIFT % 360., 1., 0.6);
+        let mut logo = commands.spawn((Logo::new(velocity, color), Visibility::Hidden));
+
+        if let Some(ref text) = settings.text {
+            logo.insert((
+                Text2d::new(text),
+                TextFont {
+                    font: font.clone(),
+                    font_size: LOGO_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(color),
+            ));
+        } else {
+            logo.insert(Sprite {
+                image: image.clone(),
+                color,
+                custom_size: Some(Vec2::splat(LOGO_RADIUS * 2.)),
+                ..default()
+            });
+        }
+
+        if index == 0 && settings.corner {
+            logo.insert(CornerAim);
+        }
+    }
 }
 
 fn handle_resize_system(
     mut resize_events: EventReader<ResizeEvent>,
     mut visible_region: ResMut<LogoVisibleRegion>,
+    mut aimed: Query<(&mut Transform, &mut Logo), With<CornerAim>>,
 ) {
     for resize in resize_events.read() {
         let (width, height) = (resize.width * 2, resize.height * 4);
         **visible_region = get_visible_region((width as u32, height as u32));
+
+        for (mut transform, mut logo) in &mut aimed {
+            aim_for_corner(&mut transform, &mut logo, **visible_region);
+        }
+    }
+}
+
+/// Stops with a message if a logo's image or font can't be loaded, since logos are only shown once
+/// all of them have been measured.
+fn logo_load_failure_system(
+    asset_server: Res<AssetServer>,
+    logos: Query<(&Logo, Option<&Sprite>, Option<&TextFont>)>,
+    message: Res<ExitMessage>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let assets = logos
+        .iter()
+        .filter(|(logo, ..)| !logo.measured)
+        .flat_map(|(_, sprite, font)| {
+            let image = sprite.map(|sprite| sprite.image.id().untyped());
+            image
+                .into_iter()
+                .chain(font.map(|font| font.font.id().untyped()))
+        });
+
+    for asset in assets {
+        if let LoadState::Failed(error) = asset_server.load_state(asset) {
+            if let Ok(mut message) = message.lock() {
+                *message = Some(format!("couldn't load logo: {error}"));
+            }
+            exit.send(AppExit::error());
+            return;
+        }
+    }
+}
+
+fn logo_measure_system(images: Res<Assets<Image>>, mut logos: Query<(&mut Sprite, &mut Logo)>) {
+    for (mut sprite, mut logo) in &mut logos {
+        if logo.measured {
+            continue;
+        }
+
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+
+        // Fit the longest side of the image to the logo size, keeping its aspect ratio.
+        let image_size = image.size().as_vec2();
+        let scale = LOGO_RADIUS * 2. / image_size.max_element();
+        sprite.custom_size = Some(image_size * scale);
+
+        let opaque = opaque_bounds(image).unwrap_or(URect::from_corners(UVec2::ZERO, image.size()));
+        let (min, max) = (opaque.min.as_vec2(), opaque.max.as_vec2());
+
+        // Image rows go downwards while world space goes upwards, so the y axis is flipped.
+        logo.bounds = Rect::from_corners(
+            Vec2::new(min.x - image_size.x / 2., image_size.y / 2. - max.y) * scale,
+            Vec2::new(max.x - image_size.x / 2., image_size.y / 2. - min.y) * scale,
+        );
+        logo.measured = true;
+    }
+}
+
+fn logo_text_measure_system(mut logos: Query<(&TextLayoutInfo, &mut Logo)>) {
+    for (layout, mut logo) in &mut logos {
+        if logo.measured || layout.size == Vec2::ZERO {
+            continue;
+        }
+
+        logo.bounds = Rect::from_center_size(Vec2::ZERO, layout.size);
+        logo.measured = true;
+    }
+}
+
+/// Places the logos once every one of them has been measured. The aimed logo goes first, since
+/// its aim decides where it starts, and the rest are put wherever they don't overlap a logo that
+/// is already in place, so that collisions don't push them apart with a jump on the first frame.
+fn logo_placement_system(
+    mut logos: Query<(&mut Transform, &mut Logo, &mut Visibility, Has<CornerAim>)>,
+    visible_region: Res<LogoVisibleRegion>,
+    mut rng: ResMut<LogoRng>,
+) {
+    if logos.iter().all(|(_, logo, ..)| logo.placed)
+        || logos.iter().any(|(_, logo, ..)| !logo.measured)
+    {
+        return;
+    }
+
+    let mut placed: Vec<Rect> = logos
+        .iter()
+        .filter(|(_, logo, ..)| logo.placed)
+        .map(|(transform, logo, ..)| placed_bounds(transform.translation.xy(), logo))
+        .collect();
+
+    let mut unplaced: Vec<_> = logos
+        .iter_mut()
+        .filter(|(_, logo, ..)| !logo.placed)
+        .collect();
+    unplaced.sort_by_key(|(.., aimed)| !aimed);
+
+    for (mut transform, mut logo, mut visibility, aimed) in unplaced {
+        if aimed {
+            aim_for_corner(&mut transform, &mut logo, **visible_region);
+        } else {
+            let position = clear_position(&mut rng, &placed, &logo, **visible_region);
+            transform.translation = position.extend(transform.translation.z);
+        }
+
+        placed.push(placed_bounds(transform.translation.xy(), &logo));
+        logo.placed = true;
+        *visibility = Visibility::Inherited;
     }
 }
 
 fn logo_movement_system(
     time: Res<Time>,
-    mut logo: Query<(&mut Transform, &mut Sprite, &mut Logo)>,
+    mut logo: Query<(&mut Transform, &mut Logo)>,
     visible_region: Res<LogoVisibleRegion>,
+    mut corner_hits: EventWriter<CornerHitEvent>,
 ) {
-    for (mut transform, mut sprite, mut velocity) in &mut logo {
-        let visible_area = Rectangle::from_size(**visible_region - LOGO_RADIUS * 1.95);
-        let visible_half = visible_area.half_size.extend(0.);
+    let now = time.elapsed_secs();
 
-        transform.translation += velocity.extend(0.) * time.delta_secs();
+    for (mut transform, mut logo) in &mut logo {
+        if !logo.placed {
+            continue;
+        }
 
-        if (transform.translation.x < -visible_half.x && velocity.x < 0.)
-            || (transform.translation.x > visible_half.x && velocity.x > 0.)
+        transform.translation += logo.extend(0.) * time.delta_secs();
+        let bounds = travel_bounds(**visible_region, logo.bounds);
+
+        if (transform.translation.x < bounds.min.x && logo.x < 0.)
+            || (transform.translation.x > bounds.max.x && logo.x > 0.)
         {
-            velocity.x *= -1.;
-            let next_hue = (sprite.color.hue() + 68.) % 360.;
-            sprite.color.set_hue(next_hue);
+            logo.x *= -1.;
+            logo.last_bounce_x = now;
+            shift_hue(&mut logo.color);
+
+            if now - logo.last_bounce_y <= CORNER_WINDOW {
+                corner_hits.send(CornerHitEvent {
+                    position: transform.translation.xy(),
+                });
+            }
         }
 
-        if (transform.translation.y < -visible_half.y - LOGO_RADIUS * 0.55 && velocity.y < 0.)
-            || (transform.translation.y > visible_half.y + LOGO_RADIUS * 0.55 && velocity.y > 0.)
+        if (transform.translation.y < bounds.min.y && logo.y < 0.)
+            || (transform.translation.y > bounds.max.y && logo.y > 0.)
         {
-            velocity.y *= -1.;
-            let next_hue = (sprite.color.hue() + 68.) % 360.;
-            sprite.color.set_hue(next_hue);
+            logo.y *= -1.;
+            logo.last_bounce_y = now;
+            shift_hue(&mut logo.color);
+
+            if now - logo.last_bounce_x <= CORNER_WINDOW {
+                corner_hits.send(CornerHitEvent {
+                    position: transform.translation.xy(),
+                });
+            }
+        }
+    }
+}
+
+fn logo_collision_system(mut logos: Query<(&Transform, &mut Logo)>) {
+    let mut combinations = logos.iter_combinations_mut();
+    while let Some([(a_transform, mut a), (b_transform, mut b)]) = combinations.fetch_next() {
+        if !a.placed || !b.placed {
+            continue;
+        }
+
+        let a_center = a_transform.translation.xy() + a.bounds.center();
+        let b_center = b_transform.translation.xy() + b.bounds.center();
+        let offset = a_center - b_center;
+        let penetration = a.bounds.half_size() + b.bounds.half_size() - offset.abs();
+        if penetration.x <= 0. || penetration.y <= 0. {
+            continue;
+        }
+
+        // Resolve along the axis of least overlap, pointing both logos away from each other while
+        // keeping their speeds. Pairs that are already separating on that axis are left alone.
+        let axis = if penetration.x < penetration.y { 0 } else { 1 };
+        if (a[axis] - b[axis]) * offset[axis] >= 0. {
+            continue;
+        }
+
+        a[axis] = a[axis].abs() * offset[axis].signum();
+        b[axis] = -b[axis].abs() * offset[axis].signum();
+        shift_hue(&mut a.color);
+        shift_hue(&mut b.color);
+    }
+}
+
+fn logo_color_system(
+    mut sprites: Query<(&Logo, &mut Sprite), Changed<Logo>>,
+    mut texts: Query<(&Logo, &mut TextColor), Changed<Logo>>,
+) {
+    for (logo, mut sprite) in &mut sprites {
+        sprite.color = logo.color;
+    }
+
+    for (logo, mut text_color) in &mut texts {
+        text_color.0 = logo.color;
+    }
+}
+
+fn corner_celebration_system(
+    mut commands: Commands,
+    mut events: EventReader<CornerHitEvent>,
+    mut corner_hits: ResMut<CornerHits>,
+    mut rng: ResMut<LogoRng>,
+    visible_region: Res<LogoVisibleRegion>,
+    overlays: Query<Entity, With<CelebrationOverlay>>,
+) {
+    for CornerHitEvent { position } in events.read() {
+        **corner_hits += 1;
+        // The count is just for fun, so failing to persist it shouldn't interrupt the screensaver.
+        let _ = state::save(CORNER_HITS_STATE, &corner_hits.to_string());
+
+        commands.spawn((
+            Flash(Timer::from_seconds(FLASH_SECONDS, TimerMode::Once)),
+            Sprite::from_color(Color::WHITE, **visible_region),
+            Transform::from_xyz(0., 0., 2.),
+        ));
+
+        // Launch the confetti back towards the center of the screen, away from the corner.
+        let inward = -position.signum();
+        for _ in 0..CONFETTI_AMOUNT {
+            let direction = Vec2::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)) * inward;
+            commands.spawn((
+                Confetti {
+                    velocity: direction * CONFETTI_SPEED * rng.gen_range(0.5..1.5),
+                    timer: Timer::from_seconds(
+                        rng.gen_range(1.0..CELEBRATION_SECONDS),
+                        TimerMode::Once,
+                    ),
+                },
+                Sprite::from_color(
+                    Color::hsl(rng.gen_range(0.0..360.0), 1., 0.6),
+                    Vec2::splat(2.),
+                ),
+                Transform::from_translation(position.extend(1.)),
+            ));
+        }
+
+        for overlay in &overlays {
+            commands.entity(overlay).despawn();
+        }
+        commands.spawn((
+            CelebrationOverlay(Timer::from_seconds(CELEBRATION_SECONDS, TimerMode::Once)),
+            Overlay {
+                lines: vec![Line::from(format!(" corner hits: {} ", **corner_hits))
+                    .bold()
+                    .black()
+                    .on_white()],
+                anchor: OverlayAnchor::TopRight,
+            },
+        ));
+    }
+}
+
+fn flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut Sprite, &mut Flash)>,
+) {
+    for (entity, mut sprite, mut flash) in &mut flashes {
+        flash.tick(time.delta());
+        if flash.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color.set_alpha(0.8 * flash.fraction_remaining());
+        }
+    }
+}
+
+fn confetti_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut confetti: Query<(Entity, &mut Transform, &mut Sprite, &mut Confetti)>,
+) {
+    for (entity, mut transform, mut sprite, mut piece) in &mut confetti {
+        piece.timer.tick(time.delta());
+        if piece.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        piece.velocity.y -= CONFETTI_GRAVITY * time.delta_secs();
+        transform.translation += piece.velocity.extend(0.) * time.delta_secs();
+        sprite
+            .color
+            .set_alpha(piece.timer.fraction_remaining().sqrt());
+    }
+}
+
+fn celebration_overlay_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut overlays: Query<(Entity, &mut CelebrationOverlay)>,
+) {
+    for (entity, mut timer) in &mut overlays {
+        timer.tick(time.delta());
+        if timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// A position within the visible region for `logo` that avoids the `placed` logos, trying the
+/// center first and then random spots. If no clear spot turns up, the position with the least
+/// overlap is used instead.
+fn clear_position(rng: &mut LogoRng, placed: &[Rect], logo: &Logo, visible_region: Vec2) -> Vec2 {
+    let bounds = travel_bounds(visible_region, logo.bounds);
+    let mut best = (bounds.center(), f32::INFINITY);
+
+    for attempt in 0..PLACEMENT_ATTEMPTS {
+        let position = if attempt == 0 {
+            bounds.center()
+        } else {
+            bounds.min + bounds.size() * Vec2::new(rng.gen(), rng.gen())
+        };
+        let rect = placed_bounds(position, logo);
+        let overlap: f32 = placed
+            .iter()
+            .map(|other| other.intersect(rect))
+            .filter(|overlap| !overlap.is_empty())
+            .map(|overlap| overlap.width() * overlap.height())
+            .sum();
+
+        if overlap < best.1 {
+            best = (position, overlap);
+        }
+        if overlap == 0. {
+            break;
+        }
+    }
+
+    best.0
+}
+
+/// Box around the visible part of `logo` when it is centered on `position`.
+fn placed_bounds(position: Vec2, logo: &Logo) -> Rect {
+    Rect {
+        min: position + logo.bounds.min,
+        max: position + logo.bounds.max,
+    }
+}
+
+/// Range of translations a logo can take before its visible pixels touch the edge of the screen.
+fn travel_bounds(visible_region: Vec2, logo_bounds: Rect) -> Rect {
+    Rect {
+        min: -visible_region / 2. - logo_bounds.min,
+        max: visible_region / 2. - logo_bounds.max,
+    }
+}
+
+/// Moves a logo to a position and velocity that will carry it into the top right corner after
+/// `CORNER_AIM_SECONDS`, found by running its motion backwards from the corner.
+fn aim_for_corner(transform: &mut Transform, logo: &mut Logo, visible_region: Vec2) {
+    let bounds = travel_bounds(visible_region, logo.bounds);
+    let distance = LOGO_SPEED * CORNER_AIM_SECONDS;
+    let (x, velocity_x) = rewind_axis(bounds.min.x, bounds.max.x, distance);
+    let (y, velocity_y) = rewind_axis(bounds.min.y, bounds.max.y, distance);
+
+    transform.translation = Vec3::new(x, y, transform.translation.z);
+    logo.velocity = Vec2::new(velocity_x, velocity_y);
+}
+
+fn rewind_axis(min: f32, max: f32, distance: f32) -> (f32, f32) {
+    let span = (max - min).max(f32::EPSILON);
+    let phase = distance % (span * 2.);
+    if phase <= span {
+        (max - phase, LOGO_SPEED)
+    } else {
+        (min + (phase - span), -LOGO_SPEED)
+    }
+}
+
+/// Smallest pixel rectangle containing every pixel that isn't (nearly) transparent, or `None` if
+/// the image is blank or its format can't be read back.
+fn opaque_bounds(image: &Image) -> Option<URect> {
+    let mut bounds: Option<URect> = None;
+
+    for y in 0..image.height() {
+        for x in 0..image.width() {
+            let color = image.get_color_at(x, y).ok()?;
+            if color.alpha() > LOGO_ALPHA_THRESHOLD {
+                let pixel = URect::new(x, y, x + 1, y + 1);
+                bounds = Some(bounds.map_or(pixel, |bounds| bounds.union(pixel)));
+            }
         }
     }
+
+    bounds
+}
+
+fn shift_hue(color: &mut Color) {
+    let next_hue = (color.hue() + LOGO_HUE_SHIFT) % 360.;
+    color.set_hue(next_hue);
+}
+
+fn random_sign(rng: &mut LogoRng) -> f32 {
+    if rng.gen_bool(0.5) {
+        1.
+    } else {
+        -1.
+    }
 }
 
 fn get_visible_region((width, height): (u32, u32)) -> Vec2 {
@@ -0,0 +1,167 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+use bevy::prelude::*;
+use clap::{ArgMatches, Args, FromArgMatches, ValueEnum};
+
+use super::{plugin, LogoSettings, LOGO_PATH_DVD, LOGO_PATH_TTY};
+use crate::{file_asset_path, screensaver::existing_file, Screensaver};
+
+#[derive(Args)]
+struct LogoArgs {
+    #[arg(
+        short = 'n',
+        long,
+        global = true,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        name = "COUNT",
+        help = "Number of logos bouncing off the screen edges and each other."
+    )]
+    count: u32,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Cycle multiple logos through every bundled image instead of just one."
+    )]
+    mixed: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Aim the logo so that it is guaranteed to hit a corner."
+    )]
+    corner: bool,
+
+    #[arg(
+        long,
+        global = true,
+        name = "IMAGE",
+        value_parser = existing_file,
+        help = "Bounce the provided IMAGE instead of a bundled logo. Can be repeated."
+    )]
+    image: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        name = "TEXT",
+        conflicts_with = "IMAGE",
+        help = "Bounce the provided TEXT instead of an image."
+    )]
+    text: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        name = "SOURCE",
+        conflicts_with_all = ["IMAGE", "TEXT"],
+        help = "Bounce text taken from SOURCE instead of an image."
+    )]
+    text_from: Option<TextSource>,
+
+    #[arg(
+        long,
+        global = true,
+        name = "FONT",
+        value_parser = existing_file,
+        help = "Render logo text with the provided FONT file (.ttf or .otf)."
+    )]
+    font: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TextSource {
+    Hostname,
+}
+
+impl Screensaver for LogoSettings {
+    fn name(&self) -> &'static str {
+        "logo"
+    }
+
+    fn about(&self) -> &'static str {
+        "DVD player style bouncing logo."
+    }
+
+    fn subvariants(&self) -> &'static [&'static str] {
+        &["dvd", "tty"]
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        LogoArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let LogoArgs {
+            count,
+            mixed,
+            corner,
+            image: custom_images,
+            text,
+            text_from,
+            font,
+        } = LogoArgs::from_arg_matches(matches)?;
+
+        let image = match subvariant {
+            Some("tty") => LOGO_PATH_TTY,
+            _ => LOGO_PATH_DVD,
+        };
+        let images = if custom_images.is_empty() {
+            let mut images = vec![image.into()];
+            images.extend(
+                [LOGO_PATH_DVD, LOGO_PATH_TTY]
+                    .into_iter()
+                    .filter(|other| mixed && *other != image)
+                    .map(Into::into),
+            );
+            images
+        } else {
+            custom_images
+                .iter()
+                .map(|path| file_asset_path(path))
+                .collect()
+        };
+        let text = match text_from {
+            Some(TextSource::Hostname) => Some(hostname()),
+            None => text,
+        };
+
+        *self = LogoSettings {
+            images,
+            count,
+            corner,
+            text,
+            font: font.as_deref().map(file_asset_path),
+        };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+fn hostname() -> String {
+    let from_env = env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok();
+    let from_file = || fs::read_to_string("/etc/hostname").ok();
+    let from_command = || {
+        Command::new("hostname")
+            .output()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+    };
+
+    from_env
+        .or_else(from_file)
+        .or_else(from_command)
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or("localhost".into())
+}
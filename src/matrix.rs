@@ -0,0 +1,320 @@
+use bevy::prelude::*;
+use bevy_ratatui_camera::RatatuiCamera;
+use clap::{ArgMatches, Args, FromArgMatches, ValueEnum};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use ratatui::style::Color as TerminalColor;
+
+use crate::{
+    common::{Glyph, Glyphs, Viewport},
+    screensaver::positive_speed,
+    Screensaver,
+};
+
+/// Rows per second that drops fall at, before `--speed`.
+const DROP_SPEED: std::ops::Range<f32> = 6.0..20.0;
+/// Chance each frame that any one character on the screen changes.
+const GLYPH_FLICKER: f64 = 0.02;
+/// How much of the way to white the leading character of each drop is.
+const HEAD_BRIGHTNESS: f32 = 0.8;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(MatrixRng(ChaCha8Rng::from_entropy()))
+        .init_resource::<Rain>()
+        .add_systems(Startup, matrix_setup_system)
+        .add_systems(
+            Update,
+            (handle_resize_system, rain_system, rain_draw_system).chain(),
+        );
+}
+
+#[derive(Resource, Clone)]
+pub struct MatrixSettings {
+    /// Characters the rain is made of.
+    pub chars: Vec<char>,
+    pub color: Srgba,
+    /// Multiplier for how fast the drops fall.
+    pub speed: f32,
+    /// Drops falling in each column at once, on average.
+    pub density: f32,
+}
+
+impl Default for MatrixSettings {
+    fn default() -> Self {
+        Self {
+            chars: MatrixCharset::Katakana.chars(),
+            color: Srgba::rgb_u8(0, 255, 65),
+            speed: 1.,
+            density: 1.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum MatrixCharset {
+    /// Half width katakana and digits, as in the film.
+    Katakana,
+    Binary,
+    Hex,
+}
+
+impl MatrixCharset {
+    pub fn chars(&self) -> Vec<char> {
+        match self {
+            MatrixCharset::Katakana => ('\u{FF66}'..='\u{FF9D}').chain('0'..='9').collect(),
+            MatrixCharset::Binary => vec!['0', '1'],
+            MatrixCharset::Hex => ('0'..='9').chain('A'..='F').collect(),
+        }
+    }
+}
+
+#[derive(Args)]
+struct MatrixArgs {
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MatrixCharset::Katakana,
+        name = "CHARSET",
+        help = "Characters the rain is made of."
+    )]
+    charset: MatrixCharset,
+
+    #[arg(
+        long,
+        conflicts_with = "CHARSET",
+        name = "CHARS",
+        help = "Make the rain out of the provided CHARS instead of a CHARSET."
+    )]
+    chars: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "00ff41",
+        value_parser = rain_color,
+        name = "RAIN COLOR",
+        help = "HEX COLOR of the rain."
+    )]
+    color: Srgba,
+
+    #[arg(
+        long,
+        default_value_t = 1.,
+        value_parser = positive_speed,
+        name = "SPEED",
+        help = "How fast the rain falls, as a multiple of the usual speed."
+    )]
+    speed: f32,
+
+    #[arg(
+        long,
+        default_value_t = 1.,
+        value_parser = positive_density,
+        name = "DROPS",
+        help = "Drops falling in each column at once, on average."
+    )]
+    density: f32,
+}
+
+impl Screensaver for MatrixSettings {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn about(&self) -> &'static str {
+        "Digital rain of falling glyphs."
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        MatrixArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        _subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let MatrixArgs {
+            charset,
+            chars,
+            color,
+            speed,
+            density,
+        } = MatrixArgs::from_arg_matches(matches)?;
+
+        *self = Self {
+            chars: chars
+                .map(|chars| chars.chars().filter(|char| !char.is_whitespace()).collect())
+                .filter(|chars: &Vec<char>| !chars.is_empty())
+                .unwrap_or_else(|| charset.chars()),
+            color,
+            speed,
+            density,
+        };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct MatrixRng(ChaCha8Rng);
+
+/// Characters on every cell of the screen, and the drops falling through them. Only the cells a
+/// drop has recently passed through are shown.
+#[derive(Resource, Default)]
+struct Rain {
+    width: u16,
+    height: u16,
+    glyphs: Vec<char>,
+    drops: Vec<Raindrop>,
+}
+
+struct Raindrop {
+    column: u16,
+    /// Row of the leading character, which starts above the screen.
+    head: f32,
+    /// Rows per second.
+    speed: f32,
+    length: u16,
+}
+
+#[derive(Component)]
+struct RainView;
+
+fn matrix_setup_system(mut commands: Commands) {
+    // Nothing is rendered in the scene, but the camera still fills the screen with the background
+    // color, which shows through wherever the rain isn't.
+    commands.spawn((Camera2d, RatatuiCamera::autoresize()));
+    commands.spawn((RainView, Glyphs::default()));
+}
+
+/// Starts the rain over whenever the size of the terminal changes, including the first time it
+/// is known.
+fn handle_resize_system(
+    viewport: Res<Viewport>,
+    settings: Res<MatrixSettings>,
+    mut rain: ResMut<Rain>,
+    mut rng: ResMut<MatrixRng>,
+) {
+    if (viewport.width, viewport.height) == (rain.width, rain.height) {
+        return;
+    }
+
+    let (width, height) = (viewport.width, viewport.height);
+    let glyphs = (0..width as usize * height as usize)
+        .map(|_| random_glyph(&mut rng, &settings))
+        .collect();
+    let drop_count = (width as f32 * settings.density).round() as usize;
+    let drops = (0..drop_count)
+        .map(|_| {
+            // Spread out over the height of the screen and above it, so they don't arrive at once.
+            let mut drop = new_drop(&mut rng, &settings, width, height);
+            drop.head = rng.gen_range(-(height as f32)..height as f32);
+            drop
+        })
+        .collect();
+
+    *rain = Rain {
+        width,
+        height,
+        glyphs,
+        drops,
+    };
+}
+
+fn rain_system(
+    time: Res<Time>,
+    settings: Res<MatrixSettings>,
+    mut rain: ResMut<Rain>,
+    mut rng: ResMut<MatrixRng>,
+) {
+    let Rain {
+        width,
+        height,
+        ref mut glyphs,
+        ref mut drops,
+    } = *rain;
+
+    for glyph in glyphs.iter_mut() {
+        if rng.gen_bool(GLYPH_FLICKER) {
+            *glyph = random_glyph(&mut rng, &settings);
+        }
+    }
+
+    for drop in drops.iter_mut() {
+        drop.head += drop.speed * time.delta_secs();
+
+        // Once the whole trail has fallen off the bottom, the drop starts again somewhere else.
+        if drop.head - drop.length as f32 > height as f32 {
+            *drop = new_drop(&mut rng, &settings, width, height);
+        }
+    }
+}
+
+fn rain_draw_system(
+    settings: Res<MatrixSettings>,
+    rain: Res<Rain>,
+    mut view: Query<&mut Glyphs, With<RainView>>,
+) {
+    let Ok(mut view) = view.get_single_mut() else {
+        return;
+    };
+
+    view.0.clear();
+    for drop in &rain.drops {
+        let head = drop.head.floor() as i32;
+        for step in 0..drop.length {
+            let row = head - step as i32;
+            if row < 0 || row >= rain.height as i32 {
+                continue;
+            }
+
+            let index = row as usize * rain.width as usize + drop.column as usize;
+            view.0.push(Glyph {
+                position: (drop.column, row as u16),
+                symbol: rain.glyphs[index],
+                color: trail_color(settings.color, step, drop.length),
+            });
+        }
+    }
+}
+
+fn new_drop(rng: &mut MatrixRng, settings: &MatrixSettings, width: u16, height: u16) -> Raindrop {
+    Raindrop {
+        column: rng.gen_range(0..width.max(1)),
+        head: -rng.gen_range(0.0..height.max(1) as f32),
+        speed: rng.gen_range(DROP_SPEED) * settings.speed,
+        length: rng.gen_range(4..height.max(5)),
+    }
+}
+
+fn random_glyph(rng: &mut MatrixRng, settings: &MatrixSettings) -> char {
+    *settings.chars.choose(&mut rng.0).unwrap_or(&' ')
+}
+
+/// Color of the character `step` places behind the head of a drop, bright at the head and fading
+/// toward black at the tail.
+fn trail_color(color: Srgba, step: u16, length: u16) -> TerminalColor {
+    let color = if step == 0 {
+        color.mix(&Srgba::WHITE, HEAD_BRIGHTNESS)
+    } else {
+        let fade = 1. - step as f32 / length as f32;
+        Srgba::new(color.red * fade, color.green * fade, color.blue * fade, 1.)
+    };
+
+    let [red, green, blue, _] = color.to_u8_array();
+    TerminalColor::Rgb(red, green, blue)
+}
+
+fn rain_color(value: &str) -> Result<Srgba, String> {
+    Srgba::hex(value).map_err(|_| format!("expected a hex color like 00ff41, got {value}"))
+}
+
+fn positive_density(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(density) if density.is_finite() && density > 0. => Ok(density),
+        _ => Err(format!("expected a number of drops above 0, got {value}")),
+    }
+}
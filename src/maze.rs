@@ -1,111 +1,325 @@
-use std::{collections::BTreeMap, f32::consts::PI};
+use std::{
+    collections::BTreeMap,
+    f32::consts::{PI, TAU},
+};
 
 use bevy::prelude::*;
+use bevy::render::camera::Exposure;
 use bevy_ratatui_camera::RatatuiCamera;
-use rand::{seq::SliceRandom, thread_rng};
+use generation::generate_maze;
+pub use generation::MazeAlgorithm;
+pub use layout::MazeLayout;
+pub use lighting::MazeLighting;
+pub use objects::MazeObject;
+use rand::{seq::IteratorRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+pub use walker::MazeWalker;
+use walker::WalkerState;
+
+mod blueprint;
+mod cli;
+mod generation;
+mod layout;
+mod lighting;
+mod minimap;
+mod objects;
+mod walker;
 
 pub const MAZE_WALL_PATH_BRICK: &str = "embedded://ttysvr/../assets/maze_wall_brick.png";
 pub const MAZE_WALL_PATH_HEDGE: &str = "embedded://ttysvr/../assets/maze_wall_hedge.png";
 pub const MAZE_CEILING_PATH_BRICK: &str = "embedded://ttysvr/../assets/maze_ceiling_brick.png";
 pub const MAZE_CEILING_PATH_HEDGE: &str = "embedded://ttysvr/../assets/maze_ceiling_hedge.png";
 
-#[derive(PartialEq, Debug)]
-enum MazeDirection {
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum MazeDirection {
     North,
     East,
     South,
     West,
+    Up,
+    Down,
 }
 
-type MazeGraph = BTreeMap<(i32, i32), (bool, bool, bool, bool)>;
+/// Cells by `(x, y, floor)`, with whether each of their north, east, south, west, up and down
+/// sides are open.
+type MazeGraph = BTreeMap<(i32, i32, i32), (bool, bool, bool, bool, bool, bool)>;
+
+/// Pair of neighboring cells joined by an opening in the wall between them.
+type MazePassage = ((i32, i32, i32), (i32, i32, i32));
 
-const MAZE_SIZE: i32 = 12;
 const MAZE_SCALE: f32 = 1.0;
-const MAZE_WALK_SPEED: f32 = 0.4;
-const MAZE_TURN_SPEED: f32 = 2.0;
+const MAZE_ACCELERATION_SECONDS: f32 = 0.75;
+const MAZE_BRAKE_DISTANCE: f32 = 0.5;
+const MAZE_CRAWL_SPEED: f32 = 0.1;
+const HEAD_BOB_HEIGHT: f32 = 0.015;
+const HEAD_BOB_STEPS: f32 = 2.0;
+const MAZE_FADE_SECONDS: f32 = 1.0;
+const MAZE_FADE_STOPS: f32 = 10.0;
+const MARKER_HEIGHT: f32 = 0.49;
+const EXIT_SIZE: f32 = 0.8;
+const BREADCRUMB_SIZE: f32 = 0.15;
+const STAIR_STEPS: usize = 12;
+const STAIR_RADIUS: f32 = 0.3;
 const WALL_DIMENSIONS: Vec3 = Vec3::new(1.0, 0.01, 1.0);
-const DIRECTION_LIST: &[MazeDirection] = &[
+pub(crate) const DIRECTION_LIST: &[MazeDirection] = &[
     MazeDirection::North,
     MazeDirection::East,
     MazeDirection::South,
     MazeDirection::West,
+    MazeDirection::Up,
+    MazeDirection::Down,
 ];
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        Startup,
-        (
-            (maze_generation_system, maze_setup_system).chain(),
-            lighting_setup_system,
-        ),
-    )
-    .add_systems(Update, movement_system);
+    let seed = app.world().resource::<MazeSettings>().seed;
+    app.insert_resource(MazeRng(match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_entropy(),
+    }));
+
+    if app.world().resource::<MazeSettings>().top_down {
+        app.add_plugins(blueprint::plugin);
+        return;
+    }
+
+    app.add_event::<MazeRegenerateEvent>()
+        .init_resource::<MazeFade>()
+        .init_resource::<WalkerState>()
+        .init_resource::<MazeMotion>()
+        .add_systems(
+            Startup,
+            (
+                (
+                    marker_setup_system,
+                    maze_generation_system,
+                    maze_setup_system,
+                    objects::objects_setup_system,
+                    lighting::intersection_lights_system,
+                )
+                    .chain(),
+                camera_setup_system,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                movement_system.run_if(not(maze_fading_out)),
+                exit_marker_system.run_if(resource_changed::<MazeGoal>),
+                maze_fade_system,
+                (
+                    maze_generation_system,
+                    maze_setup_system,
+                    objects::objects_setup_system,
+                    lighting::intersection_lights_system,
+                )
+                    .chain()
+                    .run_if(on_event::<MazeRegenerateEvent>),
+            )
+                .chain(),
+        )
+        .add_plugins((objects::plugin, minimap::plugin, lighting::plugin));
 }
 
-#[derive(Resource)]
-pub struct MazePaths(pub String, pub String);
+#[derive(Resource, Clone)]
+pub struct MazeSettings {
+    pub wall: String,
+    pub ceiling: String,
+    pub width: u32,
+    pub height: u32,
+    /// Number of floors stacked on top of each other, joined by spiral staircases.
+    pub floors: u32,
+    pub algorithm: MazeAlgorithm,
+    /// Fade into a newly generated maze whenever the goal is reached.
+    pub endless: bool,
+    pub walker: MazeWalker,
+    /// Cells walked per second.
+    pub walk_speed: f32,
+    /// Radians turned per second, on average.
+    pub turn_speed: f32,
+    pub head_bob: bool,
+    /// Seed for generating mazes and every other random choice, so that runs can be repeated.
+    pub seed: Option<u64>,
+    pub objects: Vec<MazeObject>,
+    /// Show a map of the explored cells in the corner.
+    pub minimap: bool,
+    pub lighting: MazeLighting,
+    /// Only draw the maze being generated, from above, instead of walking through it.
+    pub top_down: bool,
+    /// Walk this maze instead of generating one, ignoring the size, floors and algorithm.
+    pub layout: Option<MazeLayout>,
+}
+
+impl Default for MazeSettings {
+    fn default() -> Self {
+        Self {
+            wall: MAZE_WALL_PATH_BRICK.into(),
+            ceiling: MAZE_CEILING_PATH_BRICK.into(),
+            width: 12,
+            height: 12,
+            floors: 1,
+            algorithm: MazeAlgorithm::default(),
+            endless: false,
+            walker: MazeWalker::default(),
+            walk_speed: 0.4,
+            turn_speed: 2.0,
+            head_bob: false,
+            seed: None,
+            objects: vec![],
+            minimap: false,
+            lighting: MazeLighting::default(),
+            top_down: false,
+            layout: None,
+        }
+    }
+}
+
+impl MazeSettings {
+    /// Cell the walk starts from, in the south west corner unless a layout says otherwise.
+    fn start(&self) -> (i32, i32, i32) {
+        self.layout.as_ref().map_or((0, 0, 0), MazeLayout::start)
+    }
+}
 
 #[derive(Resource, Deref)]
 struct Maze(MazeGraph);
 
 #[derive(Resource, Deref, DerefMut)]
-struct MazeTarget((i32, i32));
+struct MazeRng(ChaCha8Rng);
+
+/// Smoothed state of the camera's walk through the maze.
+#[derive(Resource, Default)]
+struct MazeMotion {
+    speed: f32,
+    turn: Option<MazeTurn>,
+    bob_phase: f32,
+    bob: f32,
+}
 
-fn lighting_setup_system(mut ambient: ResMut<AmbientLight>) {
-    ambient.brightness = 2000.0;
+struct MazeTurn {
+    angle: f32,
+    progress: f32,
+    eased: f32,
 }
 
-fn maze_generation_system(mut commands: Commands) {
-    let mut maze: MazeGraph = BTreeMap::new();
-    let mut unresolved = vec![(0, 0)];
-    let mut rng = thread_rng();
+#[derive(Resource, Deref, DerefMut)]
+struct MazeTarget((i32, i32, i32));
 
-    loop {
-        let Some(current) = unresolved.pop() else {
-            break;
-        };
+/// Exit cell, starting in the opposite corner from the start on the top floor. Endless mazes are
+/// regenerated once it is reached, otherwise it moves somewhere else.
+#[derive(Resource, Deref, DerefMut)]
+struct MazeGoal((i32, i32, i32));
 
-        let valid_candidates: Vec<&MazeDirection> = DIRECTION_LIST
-            .iter()
-            .filter(|direction| {
-                let candidate = adjacent_node(current, direction);
-                (0..MAZE_SIZE).contains(&candidate.0)
-                    && (0..MAZE_SIZE).contains(&candidate.1)
-                    && !maze.contains_key(&candidate)
-            })
-            .collect();
+#[derive(Resource)]
+struct MazeMarkers {
+    exit_mesh: Handle<Mesh>,
+    exit_material: Handle<StandardMaterial>,
+    breadcrumb_mesh: Handle<Mesh>,
+    breadcrumb_material: Handle<StandardMaterial>,
+}
 
-        let Some(next_direction) = valid_candidates.choose(&mut rng) else {
-            continue;
-        };
+/// Materials of the current maze's walls and ceiling, so that they can be retextured in place.
+#[derive(Resource)]
+struct MazeMaterials {
+    wall: Handle<StandardMaterial>,
+    ceiling: Handle<StandardMaterial>,
+}
 
-        let next = adjacent_node(current, next_direction);
+#[derive(Component)]
+struct MazeExit;
 
-        match next_direction {
-            MazeDirection::North => {
-                maze.entry(current).or_default().0 = true;
-                maze.entry(next).or_default().2 = true;
-            }
-            MazeDirection::East => {
-                maze.entry(current).or_default().1 = true;
-                maze.entry(next).or_default().3 = true;
-            }
-            MazeDirection::South => {
-                maze.entry(current).or_default().2 = true;
-                maze.entry(next).or_default().0 = true;
-            }
-            MazeDirection::West => {
-                maze.entry(current).or_default().3 = true;
-                maze.entry(next).or_default().1 = true;
-            }
-        }
+#[derive(Component)]
+struct MazeBreadcrumb;
+
+/// Marks entities that belong to the current maze, and get replaced when it is regenerated.
+#[derive(Component)]
+struct MazeGeometry;
+
+#[derive(Event, Default)]
+struct MazeRegenerateEvent;
+
+#[derive(Resource, Default)]
+struct MazeFade {
+    state: MazeFadeState,
+    timer: Timer,
+}
+
+#[derive(Default, PartialEq, Debug)]
+enum MazeFadeState {
+    #[default]
+    Idle,
+    Out,
+    In,
+}
+
+impl MazeFade {
+    fn start(&mut self, state: MazeFadeState) {
+        self.state = state;
+        self.timer = Timer::from_seconds(MAZE_FADE_SECONDS, TimerMode::Once);
+    }
+}
+
+fn marker_setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(MazeMarkers {
+        exit_mesh: meshes.add(Cuboid::new(
+            EXIT_SIZE * MAZE_SCALE,
+            EXIT_SIZE * MAZE_SCALE,
+            0.005,
+        )),
+        exit_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.3, 1.0, 0.5),
+            unlit: true,
+            ..default()
+        }),
+        breadcrumb_mesh: meshes.add(Cuboid::new(
+            BREADCRUMB_SIZE * MAZE_SCALE,
+            BREADCRUMB_SIZE * MAZE_SCALE,
+            0.005,
+        )),
+        breadcrumb_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.8, 0.2),
+            unlit: true,
+            ..default()
+        }),
+    });
+}
 
-        unresolved.push(current);
-        unresolved.push(next);
+fn maze_generation_system(
+    mut commands: Commands,
+    settings: Res<MazeSettings>,
+    geometry: Query<Entity, With<MazeGeometry>>,
+    mut walker: ResMut<WalkerState>,
+    mut rng: ResMut<MazeRng>,
+) {
+    for entity in &geometry {
+        commands.entity(entity).despawn_recursive();
     }
+    walker.clear();
+
+    let (maze, goal) = match settings.layout {
+        Some(ref layout) => (layout.maze().clone(), layout.end()),
+        None => (
+            generate_maze(
+                settings.width as i32,
+                settings.height as i32,
+                settings.floors as i32,
+                settings.algorithm,
+                &mut **rng,
+            ),
+            (
+                settings.width as i32 - 1,
+                settings.height as i32 - 1,
+                settings.floors as i32 - 1,
+            ),
+        ),
+    };
 
     commands.insert_resource(Maze(maze));
-    commands.insert_resource(MazeTarget((0, 0)));
+    commands.insert_resource(MazeTarget(settings.start()));
+    commands.insert_resource(MazeGoal(goal));
 }
 
 fn maze_setup_system(
@@ -114,17 +328,29 @@ fn maze_setup_system(
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    paths: Res<MazePaths>,
+    settings: Res<MazeSettings>,
+    goal: Res<MazeGoal>,
+    markers: Res<MazeMarkers>,
 ) {
-    let MazePaths(ref wall_path, ref ceiling_path) = *paths;
+    let MazeSettings {
+        wall: ref wall_path,
+        ceiling: ref ceiling_path,
+        ..
+    } = *settings;
 
-    let wall_mesh = meshes.add(Cuboid::from_size(Vec3::new(
+    let wall_mesh = Mesh::from(Cuboid::from_size(Vec3::new(
         WALL_DIMENSIONS.x * MAZE_SCALE,
         WALL_DIMENSIONS.y * MAZE_SCALE,
         WALL_DIMENSIONS.z * MAZE_SCALE,
     )));
 
-    let floor_ceiling_mesh = meshes.add(Cuboid::from_size(Vec3::new(MAZE_SCALE, MAZE_SCALE, 0.01)));
+    let floor_ceiling_mesh = Mesh::from(Cuboid::from_size(Vec3::new(MAZE_SCALE, MAZE_SCALE, 0.01)));
+
+    let step_mesh = Mesh::from(Cuboid::new(
+        STAIR_RADIUS * MAZE_SCALE,
+        0.15 * MAZE_SCALE,
+        0.02 * MAZE_SCALE,
+    ));
 
     let wall_material = materials.add(StandardMaterial {
         base_color_texture: Some(asset_server.load(wall_path)),
@@ -144,127 +370,368 @@ fn maze_setup_system(
         ..default()
     });
 
-    for ((x, y), (north, east, south, west)) in maze.iter() {
-        let translation = target_to_vec3((*x, *y));
+    // Tiles never move, so they are merged into one mesh per material instead of spawning an
+    // entity for each of them.
+    let mut walls = None;
+    let mut floors = None;
+    let mut ceilings = None;
+
+    for (cell, (north, east, south, west, up, down)) in maze.iter() {
+        let translation = target_to_vec3(*cell);
 
         if !*north {
-            commands.spawn((
+            batch_mesh(
+                &mut walls,
+                &wall_mesh,
                 Transform::default().with_translation(translation + Vec3::Y * MAZE_SCALE * 0.5),
-                Mesh3d(wall_mesh.clone()),
-                MeshMaterial3d(wall_material.clone()),
-            ));
+            );
         }
 
         if !*east {
-            commands.spawn((
+            batch_mesh(
+                &mut walls,
+                &wall_mesh,
                 Transform::default()
                     .with_translation(translation + Vec3::X * MAZE_SCALE * 0.5)
                     .with_rotation(Quat::from_rotation_z(PI / 2.)),
-                Mesh3d(wall_mesh.clone()),
-                MeshMaterial3d(wall_material.clone()),
-            ));
+            );
         }
 
-        if !*south && *y == 0 {
-            commands.spawn((
+        // Cells only build their own north and east walls, unless there is no cell on the other
+        // side to build the wall between them.
+        if !*south && !maze.contains_key(&adjacent_node(*cell, &MazeDirection::South)) {
+            batch_mesh(
+                &mut walls,
+                &wall_mesh,
                 Transform::default()
                     .with_translation(translation - Vec3::Y * MAZE_SCALE * 0.5)
                     .with_rotation(Quat::from_rotation_z(PI)),
-                Mesh3d(wall_mesh.clone()),
-                MeshMaterial3d(wall_material.clone()),
-            ));
+            );
         }
 
-        if !*west && *x == 0 {
-            commands.spawn((
+        if !*west && !maze.contains_key(&adjacent_node(*cell, &MazeDirection::West)) {
+            batch_mesh(
+                &mut walls,
+                &wall_mesh,
                 Transform::default()
                     .with_translation(translation - Vec3::X * MAZE_SCALE * 0.5)
                     .with_rotation(Quat::from_rotation_z(PI * 3. / 2.)),
-                Mesh3d(wall_mesh.clone()),
-                MeshMaterial3d(wall_material.clone()),
+            );
+        }
+
+        if !*down {
+            batch_mesh(
+                &mut floors,
+                &floor_ceiling_mesh,
+                Transform::default()
+                    .with_translation(translation - Vec3::Z * 0.5 * MAZE_SCALE * WALL_DIMENSIONS.z),
+            );
+        }
+
+        if !*up {
+            batch_mesh(
+                &mut ceilings,
+                &floor_ceiling_mesh,
+                Transform::default()
+                    // Sits just below the floor of any cell above, rather than overlapping it.
+                    .with_translation(
+                        translation + Vec3::Z * (0.5 * MAZE_SCALE * WALL_DIMENSIONS.z - 0.01),
+                    ),
+            );
+            continue;
+        }
+
+        // Steps spiral up around the open middle of the cell to the floor above, leaving room for
+        // the camera to climb straight up through it.
+        for step in 0..STAIR_STEPS {
+            let turn = step as f32 / STAIR_STEPS as f32;
+            let rotation = Quat::from_rotation_z(turn * TAU);
+            let offset = Vec3::new(
+                STAIR_RADIUS * MAZE_SCALE,
+                0.,
+                (turn - 0.5) * MAZE_SCALE * WALL_DIMENSIONS.z,
+            );
+
+            batch_mesh(
+                &mut floors,
+                &step_mesh,
+                Transform::from_translation(translation + rotation * offset)
+                    .with_rotation(rotation),
+            );
+        }
+    }
+
+    for (batch, material) in [
+        (walls, &wall_material),
+        (floors, &floor_material),
+        (ceilings, &ceiling_material),
+    ] {
+        if let Some(mesh) = batch {
+            commands.spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(material.clone()),
+                MazeGeometry,
             ));
         }
+    }
 
-        commands.spawn((
-            Transform::default()
-                .with_translation(translation - Vec3::Z * 0.5 * MAZE_SCALE * WALL_DIMENSIONS.z),
-            Mesh3d(floor_ceiling_mesh.clone()),
-            MeshMaterial3d(floor_material.clone()),
-        ));
+    commands.spawn((
+        marker_transform(**goal),
+        Mesh3d(markers.exit_mesh.clone()),
+        MeshMaterial3d(markers.exit_material.clone()),
+        MazeExit,
+        MazeGeometry,
+    ));
+
+    commands.insert_resource(MazeMaterials {
+        wall: wall_material,
+        ceiling: ceiling_material,
+    });
+}
 
-        commands.spawn((
-            Transform::default()
-                .with_translation(translation + Vec3::Z * 0.5 * MAZE_SCALE * WALL_DIMENSIONS.z),
-            Mesh3d(floor_ceiling_mesh.clone()),
-            MeshMaterial3d(ceiling_material.clone()),
-        ));
+/// Adds a copy of `mesh` placed by `transform` to `batch`, starting it if it is still empty.
+fn batch_mesh(batch: &mut Option<Mesh>, mesh: &Mesh, transform: Transform) {
+    let placed = mesh.clone().transformed_by(transform);
+    match batch {
+        Some(batch) => batch.merge(&placed),
+        None => *batch = Some(placed),
     }
+}
 
-    commands
-        .spawn((
-            Msaa::Sample8,
-            RatatuiCamera::autoresize().with_autoresize_fn(|(w, h)| (w * 4, h * 4)),
-            Camera3d::default(),
-            Projection::Perspective(PerspectiveProjection {
-                fov: PI / 2.,
-                ..default()
-            }),
-            Transform::default().looking_at(Vec3::Y, Vec3::Z),
-        ))
-        .with_children(|commands| {
-            commands.spawn(PointLight {
-                intensity: 10_000.,
+fn camera_setup_system(mut commands: Commands, settings: Res<MazeSettings>) {
+    let mut camera = commands.spawn((
+        Msaa::Sample8,
+        RatatuiCamera::autoresize().with_autoresize_fn(|(w, h)| (w * 4, h * 4)),
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: PI / 2.,
+            ..default()
+        }),
+        Exposure::default(),
+        starting_camera_transform(&settings),
+    ));
+
+    if let Some(fog) = lighting::distance_fog(&settings.lighting) {
+        camera.insert(fog);
+    }
+
+    camera.with_children(|commands| {
+        commands.spawn((
+            PointLight {
+                intensity: settings.lighting.lantern,
                 ..default()
-            });
-        });
+            },
+            lighting::Lantern,
+        ));
+    });
 }
 
 fn movement_system(
+    mut commands: Commands,
     time: Res<Time>,
     maze: Res<Maze>,
+    settings: Res<MazeSettings>,
+    markers: Res<MazeMarkers>,
+    mut goal: ResMut<MazeGoal>,
     mut target: ResMut<MazeTarget>,
+    mut fade: ResMut<MazeFade>,
+    mut walker: ResMut<WalkerState>,
+    mut motion: ResMut<MazeMotion>,
+    mut rng: ResMut<MazeRng>,
     mut camera: Query<&mut Transform, With<Camera3d>>,
 ) {
     let delta = time.delta_secs();
     let mut camera_transform = camera.single_mut();
     let target_vec = target_to_vec3(**target);
+    let top_speed = settings.walk_speed * MAZE_SCALE;
+
+    // Head-bob only ever nudges the camera up and down, so walking ignores it.
+    let mut position = camera_transform.translation - Vec3::Z * motion.bob;
+    let heading = target_vec - position;
+
+    if let Some(ref mut turn) = motion.turn {
+        // Turning speeds up and slows down again along a smoothstep curve.
+        turn.progress = (turn.progress + delta * settings.turn_speed / turn.angle.abs()).min(1.);
+        let eased = turn.angle * smoothstep(turn.progress);
+        camera_transform.rotate_z(eased - turn.eased);
+        turn.eased = eased;
+
+        if turn.progress >= 1. {
+            motion.turn = None;
+
+            // Snap exactly onto the target, keeping the camera's current up, which polyhedra can
+            // turn upside down.
+            let up = camera_transform.up();
+            camera_transform.look_to(heading, up);
+        }
+    } else if heading.length() > 0.0001 {
+        // Stairs are climbed straight up or down, without turning.
+        let angle = if heading.xy().length() > 0.0001 {
+            camera_transform.forward().xy().angle_to(heading.xy())
+        } else {
+            0.
+        };
 
-    let camera_target_dot = camera_transform
-        .forward()
-        .dot((target_vec - camera_transform.translation).normalize());
-
-    if (camera_target_dot - 1.).abs() < 0.0001 {
-        camera_transform.look_at(target_vec, Vec3::Z);
-        camera_transform.translation = camera_transform
-            .translation
-            .move_towards(target_vec, delta * MAZE_SCALE * MAZE_WALK_SPEED);
-    } else if camera_target_cross_dot(target_vec, &camera_transform) > 0. {
-        camera_transform.rotate_z(-delta * MAZE_TURN_SPEED);
-        if camera_target_cross_dot(target_vec, &camera_transform) < 0. {
-            camera_transform.look_at(target_vec, Vec3::Z);
+        if angle.abs() > 0.001 {
+            motion.speed = 0.;
+            motion.turn = Some(MazeTurn {
+                angle,
+                progress: 0.,
+                eased: 0.,
+            });
+        } else {
+            // Pick up speed leaving a cell, and slow down into any cell where the walk might turn.
+            let remaining = heading.length();
+            let limit = match maze.get(&**target) {
+                Some(
+                    (true, false, true, false, false, false)
+                    | (false, true, false, true, false, false),
+                ) if **target != **goal => top_speed,
+                _ => {
+                    top_speed
+                        * (remaining / (MAZE_BRAKE_DISTANCE * MAZE_SCALE))
+                            .sqrt()
+                            .clamp(MAZE_CRAWL_SPEED, 1.)
+                }
+            };
+            motion.speed =
+                (motion.speed + top_speed * delta / MAZE_ACCELERATION_SECONDS).min(limit);
+
+            let step = (motion.speed * delta).min(remaining);
+            position = position.move_towards(target_vec, step);
+            motion.bob_phase += step / MAZE_SCALE * HEAD_BOB_STEPS * PI;
         }
+    }
+
+    motion.bob = if settings.head_bob && top_speed > 0. {
+        HEAD_BOB_HEIGHT * MAZE_SCALE * motion.bob_phase.sin() * motion.speed / top_speed
     } else {
-        camera_transform.rotate_z(delta * MAZE_TURN_SPEED);
-        if camera_target_cross_dot(target_vec, &camera_transform) > 0. {
-            camera_transform.look_at(target_vec, Vec3::Z);
+        0.
+    };
+    camera_transform.translation = position + Vec3::Z * motion.bob;
+
+    if motion.turn.is_none() && position.distance(target_vec) < 0.0001 {
+        if **target == **goal {
+            if settings.endless {
+                fade.start(MazeFadeState::Out);
+                return;
+            }
+
+            // Move the exit somewhere new and start a fresh trail towards it.
+            walker.clear();
+            if let Some(next_goal) = maze
+                .keys()
+                .filter(|cell| *cell != &**target)
+                .choose(&mut **rng)
+            {
+                **goal = *next_goal;
+            }
+        }
+
+        if !walker.has_visited(**target) {
+            commands.spawn((
+                marker_transform(**target),
+                Mesh3d(markers.breadcrumb_mesh.clone()),
+                MeshMaterial3d(markers.breadcrumb_material.clone()),
+                MazeBreadcrumb,
+                MazeGeometry,
+            ));
         }
-    }
 
-    if camera_transform.translation.distance(target_vec) < 0.01 {
-        camera_transform.translation = target_vec;
-        let node_edges = maze.get(&target).unwrap();
         let facing_direction = camera_direction(&camera_transform);
-        let next_direction = next_valid_direction(&facing_direction, node_edges);
-        **target = adjacent_node(**target, &next_direction);
+        **target = walker.next_cell(settings.walker, &maze, **target, &facing_direction, **goal);
     };
 }
 
-fn adjacent_node((x, y): (i32, i32), direction: &MazeDirection) -> (i32, i32) {
+/// Moves the exit tile to a relocated goal, clearing the breadcrumbs left on the way to the old one.
+fn exit_marker_system(
+    mut commands: Commands,
+    goal: Res<MazeGoal>,
+    mut exits: Query<&mut Transform, With<MazeExit>>,
+    breadcrumbs: Query<Entity, With<MazeBreadcrumb>>,
+) {
+    for mut transform in &mut exits {
+        *transform = marker_transform(**goal);
+    }
+
+    for entity in &breadcrumbs {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn marker_transform(cell: (i32, i32, i32)) -> Transform {
+    Transform::from_translation(
+        target_to_vec3(cell) - Vec3::Z * MARKER_HEIGHT * MAZE_SCALE * WALL_DIMENSIONS.z,
+    )
+}
+
+fn maze_fading_out(fade: Res<MazeFade>) -> bool {
+    fade.state == MazeFadeState::Out
+}
+
+/// Darkens the camera's exposure while leaving a finished maze, and brightens it again once the
+/// next maze has been generated.
+fn maze_fade_system(
+    time: Res<Time>,
+    settings: Res<MazeSettings>,
+    mut fade: ResMut<MazeFade>,
+    mut camera: Query<(&mut Transform, &mut Exposure), With<Camera3d>>,
+    mut motion: ResMut<MazeMotion>,
+    mut regenerate: EventWriter<MazeRegenerateEvent>,
+) {
+    if fade.state == MazeFadeState::Idle {
+        return;
+    }
+
+    let Ok((mut camera_transform, mut exposure)) = camera.get_single_mut() else {
+        return;
+    };
+
+    fade.timer.tick(time.delta());
+    let darkness = match fade.state {
+        MazeFadeState::Out => fade.timer.fraction(),
+        _ => fade.timer.fraction_remaining(),
+    };
+    exposure.ev100 = Exposure::default().ev100 + MAZE_FADE_STOPS * darkness;
+
+    if fade.timer.finished() {
+        if fade.state == MazeFadeState::Out {
+            regenerate.send_default();
+            *camera_transform = starting_camera_transform(&settings);
+            *motion = MazeMotion::default();
+            fade.start(MazeFadeState::In);
+        } else {
+            fade.state = MazeFadeState::Idle;
+        }
+    }
+}
+
+fn starting_camera_transform(settings: &MazeSettings) -> Transform {
+    Transform::from_translation(target_to_vec3(settings.start())).looking_to(Vec3::Y, Vec3::Z)
+}
+
+pub(crate) fn adjacent_node(
+    (x, y, z): (i32, i32, i32),
+    direction: &MazeDirection,
+) -> (i32, i32, i32) {
     match direction {
-        MazeDirection::North => (x, y + 1),
-        MazeDirection::East => (x + 1, y),
-        MazeDirection::South => (x, y - 1),
-        MazeDirection::West => (x - 1, y),
+        MazeDirection::North => (x, y + 1, z),
+        MazeDirection::East => (x + 1, y, z),
+        MazeDirection::South => (x, y - 1, z),
+        MazeDirection::West => (x - 1, y, z),
+        MazeDirection::Up => (x, y, z + 1),
+        MazeDirection::Down => (x, y, z - 1),
+    }
+}
+
+fn opposite(direction: &MazeDirection) -> MazeDirection {
+    match direction {
+        MazeDirection::North => MazeDirection::South,
+        MazeDirection::East => MazeDirection::West,
+        MazeDirection::South => MazeDirection::North,
+        MazeDirection::West => MazeDirection::East,
+        MazeDirection::Up => MazeDirection::Down,
+        MazeDirection::Down => MazeDirection::Up,
     }
 }
 
@@ -284,45 +751,109 @@ fn camera_direction(camera_transform: &Transform) -> MazeDirection {
     }
 }
 
-fn next_valid_direction(
-    facing: &MazeDirection,
-    (north, east, south, west): &(bool, bool, bool, bool),
-) -> MazeDirection {
-    let mut direction_order = vec![
-        MazeDirection::North,
-        MazeDirection::East,
-        MazeDirection::South,
-        MazeDirection::West,
-    ];
-
-    while *facing != direction_order[1] {
+/// Directions in the order a wall-follower tries them after arriving from `arrival`: clockwise
+/// starting from the left, with the stairs after the west side, and turning back last.
+fn direction_order(arrival: &MazeDirection) -> Vec<MazeDirection> {
+    let mut direction_order = DIRECTION_LIST.to_vec();
+
+    while *arrival != direction_order[direction_order.len() - 1] {
         direction_order.rotate_left(1);
     }
 
-    for direction in direction_order {
-        if match direction {
-            MazeDirection::North => *north,
-            MazeDirection::East => *east,
-            MazeDirection::South => *south,
-            MazeDirection::West => *west,
-        } {
-            return direction;
-        }
+    direction_order
+}
+
+fn is_open(
+    direction: &MazeDirection,
+    (north, east, south, west, up, down): &(bool, bool, bool, bool, bool, bool),
+) -> bool {
+    match direction {
+        MazeDirection::North => *north,
+        MazeDirection::East => *east,
+        MazeDirection::South => *south,
+        MazeDirection::West => *west,
+        MazeDirection::Up => *up,
+        MazeDirection::Down => *down,
     }
+}
 
-    unreachable!();
+/// First open side in wall-following order, or `None` for a cell with no openings at all, which
+/// only happens when it is the whole maze.
+fn next_valid_direction(
+    arrival: &MazeDirection,
+    edges: &(bool, bool, bool, bool, bool, bool),
+) -> Option<MazeDirection> {
+    direction_order(arrival)
+        .into_iter()
+        .find(|direction| is_open(direction, edges))
 }
 
-fn target_to_vec3(target: (i32, i32)) -> Vec3 {
+fn target_to_vec3(target: (i32, i32, i32)) -> Vec3 {
     Vec3::new(
         (target).0 as f32 * MAZE_SCALE,
         target.1 as f32 * MAZE_SCALE,
-        0.,
+        target.2 as f32 * MAZE_SCALE * WALL_DIMENSIONS.z,
     )
 }
 
-fn camera_target_cross_dot(target_vec: Vec3, camera_transform: &Transform) -> f32 {
-    let target_dir = target_vec - camera_transform.translation;
-    let cross = target_dir.cross(*camera_transform.forward());
-    cross.dot(Vec3::Z)
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3. - 2. * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entities spawned for a freshly set up maze, in a headless app without any rendering.
+    fn geometry_entities(width: u32, height: u32, floors: u32) -> usize {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .insert_resource(MazeSettings {
+                width,
+                height,
+                floors,
+                seed: Some(0),
+                ..default()
+            })
+            .insert_resource(MazeRng(ChaCha8Rng::seed_from_u64(0)))
+            .init_resource::<WalkerState>()
+            .add_systems(
+                Startup,
+                (
+                    marker_setup_system,
+                    maze_generation_system,
+                    maze_setup_system,
+                )
+                    .chain(),
+            );
+        app.update();
+
+        app.world_mut()
+            .query_filtered::<(), With<MazeGeometry>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn entities_do_not_grow_with_the_maze() {
+        let smallest = geometry_entities(2, 2, 1);
+        assert!(smallest > 0);
+
+        for (width, height, floors) in [
+            (8, 8, 1),
+            (32, 32, 1),
+            (64, 48, 1),
+            (16, 16, 4),
+            (48, 48, 8),
+        ] {
+            assert_eq!(
+                geometry_entities(width, height, floors),
+                smallest,
+                "{width}x{height} maze with {floors} floors"
+            );
+        }
+    }
 }
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use ratatui::style::Stylize;
+
+use super::{
+    generation::generate_passages, minimap::BrailleMap, walker::passage, MazePassage, MazeRng,
+    MazeSettings,
+};
+use crate::common::{Overlay, OverlayAnchor, Viewport};
+
+const BLUEPRINT_SECONDS: f32 = 20.;
+const BLUEPRINT_HOLD_SECONDS: f32 = 4.;
+
+/// Draws single floor mazes from above as they are generated, filling the terminal, instead of
+/// walking them.
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Blueprint>()
+        .add_systems(Startup, blueprint_setup_system)
+        .add_systems(Update, blueprint_system);
+}
+
+#[derive(Component)]
+struct BlueprintView;
+
+/// Maze being drawn, with passages appearing in the order the generation algorithm carved them.
+#[derive(Resource, Default)]
+struct Blueprint {
+    size: (i32, i32),
+    passages: Vec<MazePassage>,
+    order: BTreeMap<MazePassage, usize>,
+    carved: f32,
+    hold: Timer,
+}
+
+fn blueprint_setup_system(mut commands: Commands) {
+    commands.spawn((
+        BlueprintView,
+        Overlay {
+            lines: vec![],
+            anchor: OverlayAnchor::Center,
+        },
+    ));
+}
+
+fn blueprint_system(
+    time: Res<Time>,
+    settings: Res<MazeSettings>,
+    viewport: Res<Viewport>,
+    mut rng: ResMut<MazeRng>,
+    mut blueprint: ResMut<Blueprint>,
+    mut view: Query<&mut Overlay, With<BlueprintView>>,
+) {
+    let Ok(mut overlay) = view.get_single_mut() else {
+        return;
+    };
+
+    if viewport.width == 0 || viewport.height == 0 {
+        return;
+    }
+
+    // One maze cell per character across and two per character down fills the terminal.
+    let size = (
+        (viewport.width as i32 - 1).max(1),
+        (viewport.height as i32 * 2 - 1).max(1),
+    );
+
+    blueprint.hold.tick(time.delta());
+    if size != blueprint.size || blueprint.hold.finished() {
+        let (_, passages) = generate_passages(size.0, size.1, 1, settings.algorithm, &mut **rng);
+        *blueprint = Blueprint {
+            size,
+            order: passages
+                .iter()
+                .enumerate()
+                .map(|(index, (from, to))| (passage(*from, *to), index))
+                .collect(),
+            passages,
+            carved: 0.,
+            hold: Timer::from_seconds(BLUEPRINT_HOLD_SECONDS, TimerMode::Once),
+        };
+        blueprint.hold.pause();
+    }
+
+    let total = blueprint.passages.len() as f32;
+    blueprint.carved =
+        (blueprint.carved + time.delta_secs() * total / BLUEPRINT_SECONDS).min(total);
+    if blueprint.carved == total {
+        blueprint.hold.unpause();
+    }
+
+    let carved = blueprint.carved as usize;
+    let (width, height) = blueprint.size;
+    let mut map = BrailleMap::new(
+        blueprint.size,
+        |(x, y)| (0..width).contains(&x) && (0..height).contains(&y),
+        |(from_x, from_y), (to_x, to_y)| {
+            blueprint
+                .order
+                .get(&passage((from_x, from_y, 0), (to_x, to_y, 0)))
+                .is_some_and(|index| *index < carved)
+        },
+    );
+
+    // Highlight the passage being carved while generation is still underway.
+    let mut marks = BTreeMap::new();
+    if let Some((_, (x, y, _))) = blueprint.passages.get(carved) {
+        map.light((*x, *y));
+        let character = map.character((*x, *y));
+        marks.insert(character, map.glyph(character).to_string().yellow());
+    }
+
+    overlay.lines = map.lines(&marks);
+}
@@ -0,0 +1,274 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use clap::{error::ErrorKind, ArgMatches, Args, FromArgMatches};
+
+use super::{
+    plugin, MazeAlgorithm, MazeLayout, MazeLighting, MazeObject, MazeSettings, MazeWalker,
+    MAZE_CEILING_PATH_BRICK, MAZE_CEILING_PATH_HEDGE, MAZE_WALL_PATH_BRICK, MAZE_WALL_PATH_HEDGE,
+};
+use crate::{
+    screensaver::{existing_file, positive_speed},
+    Screensaver,
+};
+
+/// Longest side of a maze, which keeps cell coordinates well within `i32`.
+const MAX_MAZE_SIDE: u32 = 1000;
+const MAX_MAZE_FLOORS: u32 = 100;
+/// Most cells a maze can have across all of its floors.
+const MAX_MAZE_CELLS: u32 = 1_000_000;
+
+#[derive(Args)]
+struct MazeArgs {
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value = "12x12",
+        value_parser = maze_size,
+        name = "WIDTHxHEIGHT",
+        help = "Number of cells in the maze."
+    )]
+    size: (u32, u32),
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=MAX_MAZE_FLOORS as i64),
+        name = "FLOORS",
+        help = "Number of floors in the maze, joined by staircases."
+    )]
+    floors: u32,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value_t = MazeAlgorithm::DepthFirst,
+        name = "ALGORITHM",
+        help = "Algorithm used to generate the maze."
+    )]
+    algorithm: MazeAlgorithm,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Generate a new maze whenever the far corner is reached."
+    )]
+    endless: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value_t = MazeWalker::WallFollower,
+        name = "WALKER",
+        help = "How the camera finds its way through the maze."
+    )]
+    walker: MazeWalker,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 0.4,
+        value_parser = positive_speed,
+        name = "CELLS PER SECOND",
+        help = "Top walking speed."
+    )]
+    walk_speed: f32,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 2.0,
+        value_parser = positive_speed,
+        name = "RADIANS PER SECOND",
+        help = "Average turning speed."
+    )]
+    turn_speed: f32,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Bob the camera up and down while walking."
+    )]
+    head_bob: bool,
+
+    #[arg(
+        long,
+        global = true,
+        name = "SEED",
+        help = "Seed for the maze and every other random choice, to repeat a run."
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        name = "OBJECTS",
+        help = "Objects placed around the maze, separated by commas."
+    )]
+    objects: Vec<MazeObject>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Show a map of the explored part of the maze in the corner."
+    )]
+    minimap: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        name = "LIGHTING",
+        help = "Lighting preset, instead of the theme's own lighting."
+    )]
+    lighting: Option<MazeLighting>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Watch mazes being generated from above, filling the terminal."
+    )]
+    top_down: bool,
+
+    #[arg(
+        long,
+        global = true,
+        name = "LAYOUT",
+        value_parser = existing_file,
+        help = "Walk the maze drawn in the LAYOUT file, with '#' walls, '.' open cells, 'S' start and 'E' end."
+    )]
+    layout: Option<PathBuf>,
+}
+
+impl Screensaver for MazeSettings {
+    fn name(&self) -> &'static str {
+        "maze"
+    }
+
+    fn about(&self) -> &'static str {
+        "3D randomly generated maze."
+    }
+
+    fn subvariants(&self) -> &'static [&'static str] {
+        &["brick", "hedge"]
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        MazeArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let MazeArgs {
+            size: (width, height),
+            floors,
+            algorithm,
+            endless,
+            walker,
+            walk_speed,
+            turn_speed,
+            head_bob,
+            seed,
+            objects,
+            minimap,
+            lighting,
+            top_down,
+            layout,
+        } = MazeArgs::from_arg_matches(matches)?;
+
+        // Sides and floors are bounded on their own, so this can't overflow.
+        let cells = width * height * floors;
+        if !(2..=MAX_MAZE_CELLS).contains(&cells) {
+            return Err(clap::Error::raw(
+                ErrorKind::ValueValidation,
+                format!(
+                    "a maze needs between 2 and {MAX_MAZE_CELLS} cells, but {width}x{height} with \
+                     {floors} floors has {cells}\n"
+                ),
+            ));
+        }
+
+        let (wall, ceiling, theme_lighting) = match subvariant {
+            Some("hedge") => (
+                MAZE_WALL_PATH_HEDGE,
+                MAZE_CEILING_PATH_HEDGE,
+                MazeLighting::DAY_NIGHT,
+            ),
+            _ => (
+                MAZE_WALL_PATH_BRICK,
+                MAZE_CEILING_PATH_BRICK,
+                MazeLighting::CLASSIC,
+            ),
+        };
+
+        *self = MazeSettings {
+            wall: wall.into(),
+            ceiling: ceiling.into(),
+            width,
+            height,
+            floors,
+            algorithm,
+            endless,
+            walker,
+            walk_speed,
+            turn_speed,
+            head_bob,
+            seed,
+            objects,
+            minimap,
+            lighting: lighting.unwrap_or(theme_lighting),
+            top_down,
+            layout: layout.map(read_layout).transpose()?,
+        };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+/// Reads a maze layout, with an error pointing at the problem if it isn't a valid one.
+fn read_layout(path: PathBuf) -> Result<MazeLayout, clap::Error> {
+    fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| MazeLayout::parse(&text))
+        .map_err(|error| {
+            clap::Error::raw(
+                ErrorKind::InvalidValue,
+                format!("invalid maze layout {}: {error}\n", path.display()),
+            )
+        })
+}
+
+fn maze_size(value: &str) -> Result<(u32, u32), String> {
+    let error = || {
+        format!(
+            "expected WIDTHxHEIGHT with both between 1 and {MAX_MAZE_SIDE}, like 12x12, got {value}"
+        )
+    };
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(error)?;
+    let width: u32 = width.trim().parse().map_err(|_| error())?;
+    let height: u32 = height.trim().parse().map_err(|_| error())?;
+
+    let sides = 1..=MAX_MAZE_SIDE;
+    if !sides.contains(&width) || !sides.contains(&height) {
+        return Err(error());
+    }
+
+    Ok((width, height))
+}
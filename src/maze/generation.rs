@@ -0,0 +1,480 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Deref,
+};
+
+use clap::ValueEnum;
+use rand::{seq::SliceRandom, Rng};
+
+use super::{adjacent_node, MazeDirection, MazeGraph, MazePassage, DIRECTION_LIST};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum MazeAlgorithm {
+    /// Randomized depth-first backtracker, long winding corridors with few dead ends.
+    #[default]
+    #[value(name = "dfs")]
+    DepthFirst,
+    /// Randomized Prim's, many short dead ends branching off from the start.
+    Prim,
+    /// Randomized Kruskal's, joining random walls between disconnected regions.
+    Kruskal,
+    /// Wilson's loop-erased random walks, an unbiased sample of all possible mazes.
+    Wilson,
+    /// Eller's row-by-row generation.
+    Eller,
+    /// Binary tree, carving either north or east from every cell.
+    BinaryTree,
+    /// Hunt-and-kill, random walks restarted from the first unvisited cell found.
+    HuntAndKill,
+}
+
+/// Generates a perfect maze (every cell reachable from every other cell by exactly one path)
+/// spanning `width` by `height` cells on each of `floors` floors, with `(0, 0, 0)` in the south
+/// west corner of the bottom floor.
+pub fn generate_maze(
+    width: i32,
+    height: i32,
+    floors: i32,
+    algorithm: MazeAlgorithm,
+    rng: &mut impl Rng,
+) -> MazeGraph {
+    generate_passages(width, height, floors, algorithm, rng).0
+}
+
+/// Generates a maze like [`generate_maze`], along with every passage in the order it was carved,
+/// so that generation can be replayed step by step.
+pub fn generate_passages(
+    width: i32,
+    height: i32,
+    floors: i32,
+    algorithm: MazeAlgorithm,
+    rng: &mut impl Rng,
+) -> (MazeGraph, Vec<MazePassage>) {
+    let mut maze = Carving {
+        graph: BTreeMap::new(),
+        passages: vec![],
+    };
+
+    for floor in 0..floors {
+        // Each floor is generated on its own, as the algorithms only ever carve between cells
+        // of the same floor.
+        let mut level = Carving {
+            graph: BTreeMap::new(),
+            passages: vec![],
+        };
+        for x in 0..width {
+            for y in 0..height {
+                level.graph.insert((x, y, 0), Default::default());
+            }
+        }
+
+        match algorithm {
+            MazeAlgorithm::DepthFirst => depth_first(&mut level, rng),
+            MazeAlgorithm::Prim => prim(&mut level, rng),
+            MazeAlgorithm::Kruskal => kruskal(&mut level, rng),
+            MazeAlgorithm::Wilson => wilson(&mut level, rng),
+            MazeAlgorithm::Eller => eller(&mut level, width, height, rng),
+            MazeAlgorithm::BinaryTree => binary_tree(&mut level, width, height, rng),
+            MazeAlgorithm::HuntAndKill => hunt_and_kill(&mut level, rng),
+        }
+
+        let raise = |(x, y, _): (i32, i32, i32)| (x, y, floor);
+        maze.graph.extend(
+            level
+                .graph
+                .into_iter()
+                .map(|(cell, edges)| (raise(cell), edges)),
+        );
+        maze.passages.extend(
+            level
+                .passages
+                .into_iter()
+                .map(|(from, to)| (raise(from), raise(to))),
+        );
+
+        // A single staircase down to the floor below keeps the whole maze perfect.
+        if floor > 0 && width > 0 && height > 0 {
+            let stairs = (rng.gen_range(0..width), rng.gen_range(0..height), floor - 1);
+            carve(&mut maze, stairs, &MazeDirection::Up);
+        }
+    }
+
+    (maze.graph, maze.passages)
+}
+
+/// A maze in the middle of being generated, remembering the order of the passages carved so far.
+struct Carving {
+    graph: MazeGraph,
+    passages: Vec<MazePassage>,
+}
+
+impl Deref for Carving {
+    type Target = MazeGraph;
+
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+/// Removes the wall between `cell` and its neighbor in `direction`, on both sides.
+fn carve(maze: &mut Carving, cell: (i32, i32, i32), direction: &MazeDirection) {
+    let next = adjacent_node(cell, direction);
+    maze.passages.push((cell, next));
+
+    let maze = &mut maze.graph;
+
+    match direction {
+        MazeDirection::North => {
+            maze.entry(cell).or_default().0 = true;
+            maze.entry(next).or_default().2 = true;
+        }
+        MazeDirection::East => {
+            maze.entry(cell).or_default().1 = true;
+            maze.entry(next).or_default().3 = true;
+        }
+        MazeDirection::South => {
+            maze.entry(cell).or_default().2 = true;
+            maze.entry(next).or_default().0 = true;
+        }
+        MazeDirection::West => {
+            maze.entry(cell).or_default().3 = true;
+            maze.entry(next).or_default().1 = true;
+        }
+        MazeDirection::Up => {
+            maze.entry(cell).or_default().4 = true;
+            maze.entry(next).or_default().5 = true;
+        }
+        MazeDirection::Down => {
+            maze.entry(cell).or_default().5 = true;
+            maze.entry(next).or_default().4 = true;
+        }
+    }
+}
+
+/// Directions leading from `cell` to another cell of the maze.
+fn neighbors<'a>(
+    maze: &'a MazeGraph,
+    cell: (i32, i32, i32),
+) -> impl Iterator<Item = &'static MazeDirection> + 'a {
+    DIRECTION_LIST
+        .iter()
+        .filter(move |direction| maze.contains_key(&adjacent_node(cell, direction)))
+}
+
+fn depth_first(maze: &mut Carving, rng: &mut impl Rng) {
+    let Some(&start) = maze.keys().next() else {
+        return;
+    };
+
+    let mut visited = BTreeSet::from([start]);
+    let mut unresolved = vec![start];
+
+    while let Some(current) = unresolved.pop() {
+        let valid_candidates: Vec<&MazeDirection> = neighbors(maze, current)
+            .filter(|direction| !visited.contains(&adjacent_node(current, direction)))
+            .collect();
+
+        let Some(next_direction) = valid_candidates.choose(rng) else {
+            continue;
+        };
+
+        let next = adjacent_node(current, next_direction);
+        carve(maze, current, next_direction);
+        visited.insert(next);
+
+        unresolved.push(current);
+        unresolved.push(next);
+    }
+}
+
+fn prim(maze: &mut Carving, rng: &mut impl Rng) {
+    let Some(&start) = maze.keys().next() else {
+        return;
+    };
+
+    let mut visited = BTreeSet::from([start]);
+    let mut frontier: Vec<((i32, i32, i32), &MazeDirection)> = neighbors(maze, start)
+        .map(|direction| (start, direction))
+        .collect();
+
+    while !frontier.is_empty() {
+        let (cell, direction) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        let next = adjacent_node(cell, direction);
+        if !visited.insert(next) {
+            continue;
+        }
+
+        carve(maze, cell, direction);
+        frontier.extend(
+            neighbors(maze, next)
+                .filter(|direction| !visited.contains(&adjacent_node(next, direction)))
+                .map(|direction| (next, direction)),
+        );
+    }
+}
+
+fn kruskal(maze: &mut Carving, rng: &mut impl Rng) {
+    let cells: Vec<(i32, i32, i32)> = maze.keys().copied().collect();
+    let index: BTreeMap<(i32, i32, i32), usize> = cells
+        .iter()
+        .enumerate()
+        .map(|(index, cell)| (*cell, index))
+        .collect();
+
+    let mut walls: Vec<((i32, i32, i32), &MazeDirection)> = vec![];
+    for cell in &cells {
+        for direction in [&MazeDirection::North, &MazeDirection::East] {
+            if maze.contains_key(&adjacent_node(*cell, direction)) {
+                walls.push((*cell, direction));
+            }
+        }
+    }
+    walls.shuffle(rng);
+
+    let mut parents: Vec<usize> = (0..cells.len()).collect();
+    fn root(parents: &mut [usize], mut node: usize) -> usize {
+        while parents[node] != node {
+            parents[node] = parents[parents[node]];
+            node = parents[node];
+        }
+        node
+    }
+
+    for (cell, direction) in walls {
+        let a = root(&mut parents, index[&cell]);
+        let b = root(&mut parents, index[&adjacent_node(cell, direction)]);
+        if a != b {
+            parents[a] = b;
+            carve(maze, cell, direction);
+        }
+    }
+}
+
+fn wilson(maze: &mut Carving, rng: &mut impl Rng) {
+    let mut remaining: Vec<(i32, i32, i32)> = maze.keys().copied().collect();
+    remaining.shuffle(rng);
+
+    let Some(first) = remaining.pop() else {
+        return;
+    };
+    let mut in_maze = BTreeSet::from([first]);
+
+    for start in remaining {
+        if in_maze.contains(&start) {
+            continue;
+        }
+
+        // Random walk until the maze is reached, only remembering the last exit taken from each
+        // cell, which erases any loops the walk made along the way.
+        let mut exits: BTreeMap<(i32, i32, i32), &MazeDirection> = BTreeMap::new();
+        let mut current = start;
+        while !in_maze.contains(&current) {
+            let options: Vec<&MazeDirection> = neighbors(maze, current).collect();
+            let direction = *options.choose(rng).expect("cells always have a neighbor");
+            exits.insert(current, direction);
+            current = adjacent_node(current, direction);
+        }
+
+        let mut current = start;
+        while !in_maze.contains(&current) {
+            let direction = exits[&current];
+            carve(maze, current, direction);
+            in_maze.insert(current);
+            current = adjacent_node(current, direction);
+        }
+    }
+}
+
+fn eller(maze: &mut Carving, width: i32, height: i32, rng: &mut impl Rng) {
+    let mut next_set = 0;
+    let mut row_sets: BTreeMap<i32, usize> = BTreeMap::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            row_sets.entry(x).or_insert_with(|| {
+                next_set += 1;
+                next_set
+            });
+        }
+
+        // Join neighbors in different sets at random, or always on the last row so that every set
+        // ends up connected.
+        for x in 0..width - 1 {
+            let (left, right) = (row_sets[&x], row_sets[&(x + 1)]);
+            if left != right && (y == height - 1 || rng.gen_bool(0.5)) {
+                carve(maze, (x, y, 0), &MazeDirection::East);
+                for set in row_sets.values_mut() {
+                    if *set == right {
+                        *set = left;
+                    }
+                }
+            }
+        }
+
+        if y == height - 1 {
+            break;
+        }
+
+        // Every set continues north through at least one of its cells.
+        let mut members: BTreeMap<usize, Vec<i32>> = BTreeMap::new();
+        for (x, set) in &row_sets {
+            members.entry(*set).or_default().push(*x);
+        }
+
+        let mut next_row = BTreeMap::new();
+        for (set, mut xs) in members {
+            xs.shuffle(rng);
+            let carved = rng.gen_range(1..=xs.len());
+            for x in xs.into_iter().take(carved) {
+                carve(maze, (x, y, 0), &MazeDirection::North);
+                next_row.insert(x, set);
+            }
+        }
+        row_sets = next_row;
+    }
+}
+
+fn binary_tree(maze: &mut Carving, width: i32, height: i32, rng: &mut impl Rng) {
+    for x in 0..width {
+        for y in 0..height {
+            let mut options = vec![];
+            if y < height - 1 {
+                options.push(&MazeDirection::North);
+            }
+            if x < width - 1 {
+                options.push(&MazeDirection::East);
+            }
+
+            if let Some(direction) = options.choose(rng) {
+                carve(maze, (x, y, 0), direction);
+            }
+        }
+    }
+}
+
+fn hunt_and_kill(maze: &mut Carving, rng: &mut impl Rng) {
+    let Some(&start) = maze.keys().next() else {
+        return;
+    };
+
+    let mut visited = BTreeSet::from([start]);
+    let mut current = Some(start);
+
+    while let Some(cell) = current {
+        let unvisited: Vec<&MazeDirection> = neighbors(maze, cell)
+            .filter(|direction| !visited.contains(&adjacent_node(cell, direction)))
+            .collect();
+
+        if let Some(direction) = unvisited.choose(rng) {
+            let next = adjacent_node(cell, direction);
+            carve(maze, cell, direction);
+            visited.insert(next);
+            current = Some(next);
+            continue;
+        }
+
+        // Hunt for the first unvisited cell bordering the visited area, and join it to the maze.
+        current = None;
+        let cells: Vec<(i32, i32, i32)> = maze.keys().copied().collect();
+        for candidate in cells {
+            if visited.contains(&candidate) {
+                continue;
+            }
+
+            let bordering: Vec<&MazeDirection> = neighbors(maze, candidate)
+                .filter(|direction| visited.contains(&adjacent_node(candidate, direction)))
+                .collect();
+
+            if let Some(direction) = bordering.choose(rng) {
+                carve(maze, candidate, direction);
+                visited.insert(candidate);
+                current = Some(candidate);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::maze::{is_open, walker::reachable};
+
+    const ALGORITHMS: [MazeAlgorithm; 7] = [
+        MazeAlgorithm::DepthFirst,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Wilson,
+        MazeAlgorithm::Eller,
+        MazeAlgorithm::BinaryTree,
+        MazeAlgorithm::HuntAndKill,
+    ];
+
+    /// Widths, heights and floors, including single rows and columns and stacks of single cells.
+    const SIZES: [(i32, i32, i32); 13] = [
+        (1, 1, 1),
+        (1, 2, 1),
+        (2, 1, 1),
+        (1, 9, 1),
+        (9, 1, 1),
+        (2, 2, 1),
+        (5, 8, 1),
+        (12, 12, 1),
+        (1, 1, 3),
+        (1, 6, 2),
+        (6, 1, 2),
+        (4, 5, 3),
+        (7, 7, 4),
+    ];
+
+    #[test]
+    fn every_algorithm_generates_a_perfect_maze() {
+        for algorithm in ALGORITHMS {
+            for (width, height, floors) in SIZES {
+                for seed in 0..10 {
+                    let context = format!("{algorithm:?} {width}x{height}x{floors}, seed {seed}");
+                    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                    let (maze, passages) =
+                        generate_passages(width, height, floors, algorithm, &mut rng);
+                    let cells = (width * height * floors) as usize;
+
+                    assert_eq!(maze.len(), cells, "{context}: wrong number of cells");
+
+                    // A connected maze with one passage fewer than it has cells has no cycles.
+                    assert_eq!(
+                        passages.len(),
+                        cells - 1,
+                        "{context}: wrong number of passages"
+                    );
+                    assert_eq!(
+                        reachable(&maze, (0, 0, 0)).len(),
+                        cells,
+                        "{context}: not every cell is reachable from the start"
+                    );
+
+                    // Every passage is open from both sides, and none lead out of the maze.
+                    let mut openings = 0;
+                    for (cell, edges) in &maze {
+                        for direction in DIRECTION_LIST {
+                            if is_open(direction, edges) {
+                                openings += 1;
+                                assert!(
+                                    maze.contains_key(&adjacent_node(*cell, direction)),
+                                    "{context}: {cell:?} opens {direction:?} out of the maze"
+                                );
+                            }
+                        }
+                    }
+                    assert_eq!(
+                        openings,
+                        2 * passages.len(),
+                        "{context}: one-sided passages"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use super::{adjacent_node, walker::reachable, MazeDirection, MazeGraph};
+
+/// Maze drawn in ASCII art, with `#` (or a space) for walls, `.` for open cells, and `S` and `E`
+/// for the open cells where the walk starts and ends. The top line is the north side of the maze,
+/// and lines may be different lengths.
+#[derive(Clone, Debug)]
+pub struct MazeLayout {
+    maze: MazeGraph,
+    start: (i32, i32, i32),
+    end: (i32, i32, i32),
+}
+
+impl MazeLayout {
+    /// Reads a layout, checking that it has exactly one start and end, and that every open cell
+    /// can be reached from the start. Errors point at the line and column of the problem.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = text.lines().collect();
+        let rows = lines.len() as i32;
+
+        // Cells and where they were written, so that errors can point back at them.
+        let mut cells = BTreeMap::new();
+        let mut start = None;
+        let mut end = None;
+
+        for (row, line) in lines.iter().enumerate() {
+            for (column, character) in line.chars().enumerate() {
+                let position = (row + 1, column + 1);
+                let cell = (column as i32, rows - 1 - row as i32, 0);
+
+                let marker = match character {
+                    '#' | ' ' => continue,
+                    '.' => None,
+                    'S' => Some((&mut start, "start")),
+                    'E' => Some((&mut end, "end")),
+                    _ => {
+                        return Err(format!(
+                            "line {}, column {}: unexpected '{character}', expected '#', '.', 'S' \
+                             or 'E'",
+                            position.0, position.1
+                        ))
+                    }
+                };
+
+                if let Some((marker, name)) = marker {
+                    if let Some((_, (first_row, first_column))) = marker {
+                        return Err(format!(
+                            "line {}, column {}: second {name} '{character}', the first is at \
+                             line {first_row}, column {first_column}",
+                            position.0, position.1
+                        ));
+                    }
+                    *marker = Some((cell, position));
+                }
+
+                cells.insert(cell, position);
+            }
+        }
+
+        let (Some((start, _)), Some((end, _))) = (start, end) else {
+            return Err(match start {
+                None => "no start, mark one open cell with 'S'".into(),
+                Some(_) => "no end, mark one open cell with 'E'".into(),
+            });
+        };
+
+        let maze: MazeGraph = cells
+            .keys()
+            .map(|cell| {
+                let open = |direction| cells.contains_key(&adjacent_node(*cell, &direction));
+                let edges = (
+                    open(MazeDirection::North),
+                    open(MazeDirection::East),
+                    open(MazeDirection::South),
+                    open(MazeDirection::West),
+                    false,
+                    false,
+                );
+                (*cell, edges)
+            })
+            .collect();
+
+        let connected = reachable(&maze, start);
+        if let Some((line, column)) = cells
+            .iter()
+            .find(|(cell, _)| !connected.contains(*cell))
+            .map(|(_, position)| position)
+        {
+            return Err(format!(
+                "line {line}, column {column}: open cell can't be reached from the start"
+            ));
+        }
+
+        Ok(Self { maze, start, end })
+    }
+
+    pub(super) fn maze(&self) -> &MazeGraph {
+        &self.maze
+    }
+
+    pub(super) fn start(&self) -> (i32, i32, i32) {
+        self.start
+    }
+
+    pub(super) fn end(&self) -> (i32, i32, i32) {
+        self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: &str = "#####\n#S.E#\n#.###\n#####\n";
+
+    fn parse_error(text: &str) -> String {
+        MazeLayout::parse(text).expect_err("layout should be rejected")
+    }
+
+    #[test]
+    fn valid_layout_becomes_a_maze() {
+        let layout = MazeLayout::parse(LAYOUT).unwrap();
+
+        // The top line is the north side, so the bottom line of open cells is the lowest y.
+        assert_eq!(layout.start(), (1, 2, 0));
+        assert_eq!(layout.end(), (3, 2, 0));
+        assert_eq!(
+            layout.maze().keys().copied().collect::<Vec<_>>(),
+            [(1, 1, 0), (1, 2, 0), (2, 2, 0), (3, 2, 0)]
+        );
+
+        let open = |cell| layout.maze()[&cell];
+        assert_eq!(open((1, 2, 0)), (false, true, true, false, false, false));
+        assert_eq!(open((2, 2, 0)), (false, true, false, true, false, false));
+        assert_eq!(open((3, 2, 0)), (false, false, false, true, false, false));
+        assert_eq!(open((1, 1, 0)), (true, false, false, false, false, false));
+    }
+
+    #[test]
+    fn crlf_line_endings_read_the_same() {
+        let layout = MazeLayout::parse(&LAYOUT.replace('\n', "\r\n")).unwrap();
+        let expected = MazeLayout::parse(LAYOUT).unwrap();
+
+        assert_eq!(layout.maze(), expected.maze());
+        assert_eq!(layout.start(), expected.start());
+        assert_eq!(layout.end(), expected.end());
+    }
+
+    #[test]
+    fn unexpected_character_points_at_it() {
+        let error = parse_error("S..\n.x.\n..E");
+
+        assert!(
+            error.starts_with("line 2, column 2: unexpected 'x'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn second_start_or_end_points_at_both() {
+        assert_eq!(
+            parse_error("S..\n..S\n..E"),
+            "line 2, column 3: second start 'S', the first is at line 1, column 1"
+        );
+        assert_eq!(
+            parse_error("S.E\nE.."),
+            "line 2, column 1: second end 'E', the first is at line 1, column 3"
+        );
+    }
+
+    #[test]
+    fn missing_start_or_end_is_named() {
+        assert_eq!(
+            parse_error("...\n..E"),
+            "no start, mark one open cell with 'S'"
+        );
+        assert_eq!(
+            parse_error("S..\n..."),
+            "no end, mark one open cell with 'E'"
+        );
+    }
+
+    #[test]
+    fn unreachable_cell_points_at_it() {
+        assert_eq!(
+            parse_error("S.E\n###\n#.."),
+            "line 3, column 2: open cell can't be reached from the start"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use clap::{builder::PossibleValue, ValueEnum};
+use rand::{seq::IteratorRandom, Rng};
+
+use super::{is_open, target_to_vec3, Maze, MazeGeometry, MazeRng, MazeSettings, DIRECTION_LIST};
+
+const NIGHT_BRIGHTNESS: f32 = 0.05;
+const SUN_ILLUMINANCE: f32 = 2_000.;
+const INTERSECTION_LIGHT_INTENSITY: f32 = 20_000.;
+const INTERSECTION_LIGHT_RANGE: f32 = 4.;
+
+/// How the maze is lit. Each `--lighting` preset is one of the associated constants, and any of
+/// the fields can be tuned on top of them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MazeLighting {
+    /// Brightness of the light reaching every corner of the maze.
+    pub ambient: f32,
+    /// Intensity of the light carried along with the camera.
+    pub lantern: f32,
+    /// Make the camera's light flicker like a torch.
+    pub flicker: bool,
+    /// Distance at which corridors have faded into darkness.
+    pub fog: Option<f32>,
+    /// Number of colored lights placed at random intersections.
+    pub intersection_lights: u32,
+    /// Seconds for a full day and night, for open air themes like the hedge.
+    pub day_length: Option<f32>,
+}
+
+impl MazeLighting {
+    pub const CLASSIC: Self = Self {
+        ambient: 2_000.,
+        lantern: 10_000.,
+        flicker: false,
+        fog: None,
+        intersection_lights: 0,
+        day_length: None,
+    };
+
+    pub const FOG: Self = Self {
+        fog: Some(4.),
+        ..Self::CLASSIC
+    };
+
+    pub const TORCH: Self = Self {
+        ambient: 100.,
+        lantern: 8_000.,
+        flicker: true,
+        fog: Some(5.),
+        ..Self::CLASSIC
+    };
+
+    pub const COLORED: Self = Self {
+        ambient: 300.,
+        lantern: 3_000.,
+        fog: Some(8.),
+        intersection_lights: 8,
+        ..Self::CLASSIC
+    };
+
+    pub const DAY_NIGHT: Self = Self {
+        lantern: 4_000.,
+        day_length: Some(120.),
+        ..Self::CLASSIC
+    };
+}
+
+impl Default for MazeLighting {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+impl ValueEnum for MazeLighting {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self::CLASSIC,
+            Self::FOG,
+            Self::TORCH,
+            Self::COLORED,
+            Self::DAY_NIGHT,
+        ]
+    }
+
+    /// Only the presets have a name, lighting tuned away from them has none.
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let (name, help) = match *self {
+            lighting if lighting == Self::CLASSIC => ("classic", "Evenly lit corridors."),
+            lighting if lighting == Self::FOG => ("fog", "Corridors fading into the distance."),
+            lighting if lighting == Self::TORCH => {
+                ("torch", "Darkness, apart from a flickering torch.")
+            }
+            lighting if lighting == Self::COLORED => {
+                ("colored", "Colored lights at some of the intersections.")
+            }
+            lighting if lighting == Self::DAY_NIGHT => (
+                "day-night",
+                "A sun crossing the sky, and the night after it.",
+            ),
+            _ => return None,
+        };
+
+        Some(PossibleValue::new(name).help(help))
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, lighting_setup_system).add_systems(
+        Update,
+        (
+            lantern_flicker_system.run_if(lantern_flickers),
+            day_night_system.run_if(has_day_night),
+        ),
+    );
+}
+
+/// The light carried along with the camera.
+#[derive(Component)]
+pub(super) struct Lantern;
+
+#[derive(Component)]
+struct Sun;
+
+fn lighting_setup_system(
+    mut commands: Commands,
+    settings: Res<MazeSettings>,
+    mut ambient: ResMut<AmbientLight>,
+) {
+    ambient.brightness = settings.lighting.ambient;
+
+    if settings.lighting.day_length.is_some() {
+        commands.spawn((Sun, DirectionalLight::default(), Transform::default()));
+    }
+}
+
+/// Fog hiding the far ends of corridors, to be added to the camera.
+pub(super) fn distance_fog(lighting: &MazeLighting) -> Option<DistanceFog> {
+    lighting.fog.map(|visibility| DistanceFog {
+        color: Color::BLACK,
+        falloff: FogFalloff::Linear {
+            start: visibility * 0.25,
+            end: visibility,
+        },
+        ..default()
+    })
+}
+
+/// Places colored lights at random intersections of a freshly generated maze.
+pub(super) fn intersection_lights_system(
+    mut commands: Commands,
+    maze: Res<Maze>,
+    settings: Res<MazeSettings>,
+    mut rng: ResMut<MazeRng>,
+) {
+    let intersections = maze.iter().filter(|(_, edges)| {
+        DIRECTION_LIST
+            .iter()
+            .filter(|direction| is_open(direction, edges))
+            .count()
+            >= 3
+    });
+
+    for (cell, _) in
+        intersections.choose_multiple(&mut **rng, settings.lighting.intersection_lights as usize)
+    {
+        commands.spawn((
+            PointLight {
+                color: Color::hsl(rng.gen_range(0.0..360.), 1.0, 0.5),
+                intensity: INTERSECTION_LIGHT_INTENSITY,
+                range: INTERSECTION_LIGHT_RANGE,
+                ..default()
+            },
+            Transform::from_translation(target_to_vec3(*cell)),
+            MazeGeometry,
+        ));
+    }
+}
+
+fn lantern_flickers(settings: Res<MazeSettings>) -> bool {
+    settings.lighting.flicker
+}
+
+fn has_day_night(settings: Res<MazeSettings>) -> bool {
+    settings.lighting.day_length.is_some()
+}
+
+/// Wavers the camera's light with a few out of step waves, so that it never quite repeats.
+fn lantern_flicker_system(
+    time: Res<Time>,
+    settings: Res<MazeSettings>,
+    mut lanterns: Query<&mut PointLight, With<Lantern>>,
+) {
+    let t = time.elapsed_secs();
+    let flicker =
+        0.8 + 0.12 * (t * 9.1).sin() + 0.05 * (t * 23.7 + 1.3).sin() + 0.03 * (t * 3.3).sin();
+
+    for mut lantern in &mut lanterns {
+        lantern.intensity = settings.lighting.lantern * flicker;
+        lantern.color = Color::srgb(1.0, 0.75 + 0.1 * flicker, 0.45);
+    }
+}
+
+/// Moves the sun across the sky, dimming and cooling the ambient light through the night.
+fn day_night_system(
+    time: Res<Time>,
+    settings: Res<MazeSettings>,
+    mut ambient: ResMut<AmbientLight>,
+    mut sun: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+) {
+    let Some(day_length) = settings.lighting.day_length else {
+        return;
+    };
+
+    // Start out at midday.
+    let angle = (time.elapsed_secs() / day_length + 0.25) * TAU;
+    let daylight = (angle.sin() * 0.5 + 0.5).max(NIGHT_BRIGHTNESS);
+
+    ambient.brightness = settings.lighting.ambient * daylight;
+    ambient.color = Color::srgb(0.4, 0.5, 1.0).mix(&Color::WHITE, daylight);
+
+    for (mut light, mut transform) in &mut sun {
+        light.illuminance = SUN_ILLUMINANCE * angle.sin().max(0.);
+        *transform =
+            Transform::default().looking_to(Vec3::new(-angle.cos(), 0.2, -angle.sin()), Vec3::Z);
+    }
+}
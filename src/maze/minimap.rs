@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+
+use super::{
+    camera_direction, walker::is_neighbor, Maze, MazeDirection, MazeGoal, MazeSettings, MAZE_SCALE,
+    WALL_DIMENSIONS,
+};
+use crate::common::{Overlay, OverlayAnchor};
+
+/// Bit of a braille character for each dot, indexed by row and then column.
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MinimapExplored>()
+        .add_systems(Startup, minimap_setup_system.run_if(minimap_enabled))
+        .add_systems(Update, minimap_system.run_if(minimap_enabled));
+}
+
+#[derive(Component)]
+struct Minimap;
+
+/// Cells the camera has passed through in the current maze.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct MinimapExplored(BTreeSet<(i32, i32, i32)>);
+
+/// Top-down view of a maze drawn in braille, with a dot for every wall and corner post. Each
+/// character covers one cell across and two cells down, along with the walls around them.
+pub(super) struct BrailleMap {
+    height: i32,
+    characters: Vec<Vec<u8>>,
+}
+
+impl BrailleMap {
+    /// Draws the walls around every `shown` cell of a `width` by `height` maze, leaving a gap
+    /// wherever two cells are `open` to each other.
+    pub fn new(
+        (width, height): (i32, i32),
+        shown: impl Fn((i32, i32)) -> bool,
+        open: impl Fn((i32, i32), (i32, i32)) -> bool,
+    ) -> Self {
+        let rows = (2 * height.max(0) as usize + 4) / 4;
+        let columns = width.max(0) as usize + 1;
+        let mut map = Self {
+            height,
+            characters: vec![vec![0; columns]; rows],
+        };
+
+        // Dots alternate between walls or posts on even coordinates and cells on odd ones.
+        let touching = |dot: i32| (dot / 2 - 1 + dot % 2)..=dot / 2;
+        for x in 0..=2 * width {
+            for y in 0..=2 * height {
+                let visible =
+                    touching(x).any(|cell_x| touching(y).any(|cell_y| shown((cell_x, cell_y))));
+                if !visible {
+                    continue;
+                }
+
+                let wall = match (x % 2, y % 2) {
+                    (0, 0) => true,
+                    (1, 1) => false,
+                    (1, _) => !open((x / 2, y / 2 - 1), (x / 2, y / 2)),
+                    _ => !open((x / 2 - 1, y / 2), (x / 2, y / 2)),
+                };
+
+                if wall {
+                    map.set_dot(x, y);
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Adds a dot in the middle of `cell`.
+    pub fn light(&mut self, (x, y): (i32, i32)) {
+        self.set_dot(2 * x + 1, 2 * y + 1);
+    }
+
+    /// Column and row of the character containing `cell`.
+    pub fn character(&self, (x, y): (i32, i32)) -> (usize, usize) {
+        (x as usize, (2 * (self.height - y) - 1) as usize / 4)
+    }
+
+    pub fn glyph(&self, (column, row): (usize, usize)) -> char {
+        let dots = self
+            .characters
+            .get(row)
+            .and_then(|characters| characters.get(column))
+            .copied()
+            .unwrap_or(0);
+        char::from_u32(0x2800 + dots as u32).unwrap_or(' ')
+    }
+
+    /// Lines of braille characters, replacing any characters with a span in `marks`.
+    pub fn lines(&self, marks: &BTreeMap<(usize, usize), Span<'static>>) -> Vec<Line<'static>> {
+        self.characters
+            .iter()
+            .enumerate()
+            .map(|(row, characters)| {
+                let spans: Vec<Span<'static>> = (0..characters.len())
+                    .map(|column| {
+                        marks
+                            .get(&(column, row))
+                            .cloned()
+                            .unwrap_or_else(|| self.glyph((column, row)).to_string().into())
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    fn set_dot(&mut self, x: i32, y: i32) {
+        let (x, row) = (x as usize, (2 * self.height - y) as usize);
+        if let Some(dots) = self
+            .characters
+            .get_mut(row / 4)
+            .and_then(|characters| characters.get_mut(x / 2))
+        {
+            *dots |= BRAILLE_DOTS[row % 4][x % 2];
+        }
+    }
+}
+
+fn minimap_enabled(settings: Res<MazeSettings>) -> bool {
+    settings.minimap
+}
+
+fn minimap_setup_system(mut commands: Commands) {
+    commands.spawn((
+        Minimap,
+        Overlay {
+            lines: vec![],
+            anchor: OverlayAnchor::BottomLeft,
+        },
+    ));
+}
+
+/// Redraws the minimap with the cells explored so far on the camera's floor, the exit if it is on
+/// the same floor, and an arrow for the camera.
+fn minimap_system(
+    maze: Res<Maze>,
+    goal: Res<MazeGoal>,
+    mut explored: ResMut<MinimapExplored>,
+    mut minimap: Query<&mut Overlay, With<Minimap>>,
+    camera: Query<&Transform, With<Camera3d>>,
+) {
+    let (Ok(mut overlay), Ok(camera_transform)) = (minimap.get_single_mut(), camera.get_single())
+    else {
+        return;
+    };
+
+    if maze.is_changed() {
+        explored.clear();
+    }
+
+    let position = camera_transform.translation / MAZE_SCALE;
+    let floor = (position.z / WALL_DIMENSIONS.z).round() as i32;
+    let camera_cell = (position.x.round() as i32, position.y.round() as i32);
+    explored.insert((camera_cell.0, camera_cell.1, floor));
+
+    let size = maze.keys().fold((0, 0), |(width, height), (x, y, _)| {
+        (width.max(x + 1), height.max(y + 1))
+    });
+    let mut map = BrailleMap::new(
+        size,
+        |(x, y)| explored.contains(&(x, y, floor)),
+        |(from_x, from_y), (to_x, to_y)| {
+            is_neighbor(&maze, (from_x, from_y, floor), (to_x, to_y, floor))
+        },
+    );
+
+    let arrow = match camera_direction(camera_transform) {
+        MazeDirection::North => "↑",
+        MazeDirection::East => "→",
+        MazeDirection::South => "↓",
+        MazeDirection::West => "←",
+        MazeDirection::Up | MazeDirection::Down => "•",
+    };
+
+    let mut marks = BTreeMap::new();
+    let (goal_x, goal_y, goal_floor) = **goal;
+    if goal_floor == floor {
+        map.light((goal_x, goal_y));
+        let exit = map.character((goal_x, goal_y));
+        marks.insert(exit, map.glyph(exit).to_string().green());
+    }
+    marks.insert(map.character(camera_cell), arrow.yellow());
+
+    overlay.lines = map.lines(&marks);
+}
@@ -0,0 +1,341 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use clap::ValueEnum;
+use rand::seq::{IteratorRandom, SliceRandom};
+
+use super::{
+    adjacent_node, is_open, target_to_vec3, Maze, MazeDirection, MazeFade, MazeFadeState,
+    MazeGeometry, MazeGoal, MazeMaterials, MazeRng, MazeSettings, DIRECTION_LIST,
+    MAZE_CEILING_PATH_BRICK, MAZE_CEILING_PATH_HEDGE, MAZE_SCALE, MAZE_WALL_PATH_BRICK,
+    MAZE_WALL_PATH_HEDGE,
+};
+
+const RAT_SPEED: f32 = 0.8;
+const RAT_HEIGHT: f32 = -0.45;
+const PICKUP_DISTANCE: f32 = 0.35;
+const PICKUP_SPIN_SPEED: f32 = 1.5;
+const ROLL_SPEED: f32 = PI / 2.;
+const CELLS_PER_RAT: usize = 24;
+const CELLS_PER_PICKUP: usize = 36;
+
+/// Optional inhabitants of the maze. Every kind but rats triggers an effect when the camera walks
+/// into it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum MazeObject {
+    /// Rats that wander the corridors.
+    Rats,
+    /// Spinning polyhedra that turn the view upside down.
+    Polyhedra,
+    /// Spinning tokens that swap the wall and ceiling textures.
+    Tokens,
+    /// A smiley at the exit that starts a new maze.
+    Smiley,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MazeRoll>().add_systems(
+        Update,
+        (
+            rat_system,
+            pickup_spin_system,
+            smiley_follow_system.run_if(resource_changed::<MazeGoal>),
+            pickup_system,
+            camera_roll_system,
+        ),
+    );
+}
+
+/// Roll of the camera around its forward axis, turned over by each polyhedron picked up.
+#[derive(Resource, Default)]
+pub(super) struct MazeRoll {
+    angle: f32,
+    target: f32,
+}
+
+#[derive(Component)]
+struct Rat {
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+    progress: f32,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+enum MazePickup {
+    Polyhedron,
+    Token,
+    Smiley,
+}
+
+pub(super) fn objects_setup_system(
+    mut commands: Commands,
+    maze: Res<Maze>,
+    goal: Res<MazeGoal>,
+    settings: Res<MazeSettings>,
+    mut roll: ResMut<MazeRoll>,
+    mut rng: ResMut<MazeRng>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // The camera has just been put back upright at the start.
+    *roll = MazeRoll::default();
+
+    let mut cells: Vec<(i32, i32, i32)> = maze
+        .keys()
+        .copied()
+        .filter(|cell| *cell != settings.start() && *cell != **goal)
+        .collect();
+    cells.shuffle(&mut **rng);
+    let mut cells = cells.into_iter().cycle();
+
+    if settings.objects.contains(&MazeObject::Rats) {
+        let mesh = meshes.add(Cuboid::new(
+            0.1 * MAZE_SCALE,
+            0.08 * MAZE_SCALE,
+            0.25 * MAZE_SCALE,
+        ));
+        let material = materials.add(Color::srgb(0.45, 0.4, 0.4));
+
+        for cell in cells.by_ref().take((maze.len() / CELLS_PER_RAT).max(1)) {
+            commands.spawn((
+                Rat {
+                    from: cell,
+                    to: cell,
+                    progress: 1.,
+                },
+                Transform::from_translation(
+                    target_to_vec3(cell) + Vec3::Z * RAT_HEIGHT * MAZE_SCALE,
+                ),
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                MazeGeometry,
+            ));
+        }
+    }
+
+    let pickup_amount = (maze.len() / CELLS_PER_PICKUP).max(1);
+
+    if settings.objects.contains(&MazeObject::Polyhedra) {
+        let mesh = meshes.add(
+            Tetrahedron::default()
+                .mesh()
+                .build()
+                .scaled_by(Vec3::splat(0.3 * MAZE_SCALE)),
+        );
+        for cell in cells.by_ref().take(pickup_amount) {
+            let material = materials.add(StandardMaterial {
+                base_color: Color::hsl((cell.0 * 47 + cell.1 * 89) as f32 % 360., 0.8, 0.6),
+                ..default()
+            });
+
+            commands.spawn((
+                MazePickup::Polyhedron,
+                Transform::from_translation(target_to_vec3(cell)),
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material),
+                MazeGeometry,
+            ));
+        }
+    }
+
+    if settings.objects.contains(&MazeObject::Tokens) {
+        let mesh = meshes.add(Cylinder::new(0.15 * MAZE_SCALE, 0.02 * MAZE_SCALE));
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.85, 0.3),
+            metallic: 0.8,
+            ..default()
+        });
+
+        for cell in cells.by_ref().take(pickup_amount) {
+            commands.spawn((
+                MazePickup::Token,
+                Transform::from_translation(target_to_vec3(cell)),
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                MazeGeometry,
+            ));
+        }
+    }
+
+    if settings.objects.contains(&MazeObject::Smiley) {
+        let face = meshes.add(Sphere::new(0.2 * MAZE_SCALE));
+        let feature = meshes.add(Sphere::new(0.03 * MAZE_SCALE));
+        let yellow = materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.9, 0.1),
+            unlit: true,
+            ..default()
+        });
+        let black = materials.add(StandardMaterial {
+            base_color: Color::BLACK,
+            unlit: true,
+            ..default()
+        });
+
+        commands
+            .spawn((
+                MazePickup::Smiley,
+                Transform::from_translation(target_to_vec3(**goal)),
+                Mesh3d(face),
+                MeshMaterial3d(yellow),
+                MazeGeometry,
+            ))
+            .with_children(|commands| {
+                // Eyes and a curved row of dots for a mouth, on the face's local -Y side.
+                let features = [(-0.07, 0.07), (0.07, 0.07)]
+                    .into_iter()
+                    .chain((-2..=2).map(|i| (i as f32 * 0.04, -0.06 + (i * i) as f32 * 0.008)));
+                for (x, z) in features {
+                    commands.spawn((
+                        Transform::from_xyz(x * MAZE_SCALE, -0.19 * MAZE_SCALE, z * MAZE_SCALE),
+                        Mesh3d(feature.clone()),
+                        MeshMaterial3d(black.clone()),
+                    ));
+                }
+            });
+    }
+}
+
+fn rat_system(
+    time: Res<Time>,
+    maze: Res<Maze>,
+    mut rng: ResMut<MazeRng>,
+    mut rats: Query<(&mut Rat, &mut Transform)>,
+) {
+    for (mut rat, mut transform) in &mut rats {
+        rat.progress += time.delta_secs() * RAT_SPEED / MAZE_SCALE;
+
+        if rat.progress >= 1. {
+            rat.progress = 0.;
+            let Some(edges) = maze.get(&rat.to) else {
+                continue;
+            };
+
+            // Keep scurrying forwards, only turning back at dead ends. Rats can't climb stairs.
+            let options: Vec<(i32, i32, i32)> = DIRECTION_LIST
+                .iter()
+                .filter(|direction| !matches!(direction, MazeDirection::Up | MazeDirection::Down))
+                .filter(|direction| is_open(direction, edges))
+                .map(|direction| adjacent_node(rat.to, direction))
+                .collect();
+            let next = options
+                .iter()
+                .filter(|cell| options.len() == 1 || **cell != rat.from)
+                .choose(&mut **rng)
+                .copied()
+                .unwrap_or(rat.to);
+
+            rat.from = rat.to;
+            rat.to = next;
+        }
+
+        let from = target_to_vec3(rat.from);
+        let to = target_to_vec3(rat.to);
+        transform.translation = from.lerp(to, rat.progress) + Vec3::Z * RAT_HEIGHT * MAZE_SCALE;
+        if from != to {
+            transform.look_to(to - from, Vec3::Z);
+        }
+    }
+}
+
+fn pickup_spin_system(time: Res<Time>, mut pickups: Query<(&MazePickup, &mut Transform)>) {
+    let angle = time.delta_secs() * PICKUP_SPIN_SPEED;
+
+    for (pickup, mut transform) in &mut pickups {
+        match pickup {
+            MazePickup::Polyhedron => {
+                transform.rotate_z(angle);
+                transform.rotate_local_x(angle * 0.7);
+            }
+            MazePickup::Token => {
+                // Tokens stand upright and flip over and over around the vertical axis.
+                transform.rotation = Quat::from_rotation_z(time.elapsed_secs() * PICKUP_SPIN_SPEED)
+                    * Quat::from_rotation_x(PI / 2.);
+            }
+            MazePickup::Smiley => transform.rotate_z(angle * 0.5),
+        }
+    }
+}
+
+fn smiley_follow_system(goal: Res<MazeGoal>, mut pickups: Query<(&MazePickup, &mut Transform)>) {
+    for (pickup, mut transform) in &mut pickups {
+        if *pickup == MazePickup::Smiley {
+            transform.translation = target_to_vec3(**goal);
+        }
+    }
+}
+
+fn pickup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    maze_materials: Res<MazeMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut roll: ResMut<MazeRoll>,
+    mut fade: ResMut<MazeFade>,
+    pickups: Query<(Entity, &MazePickup, &Transform)>,
+    camera: Query<&Transform, With<Camera3d>>,
+) {
+    let Ok(camera_transform) = camera.get_single() else {
+        return;
+    };
+
+    for (entity, pickup, transform) in &pickups {
+        let distance = transform.translation.distance(camera_transform.translation);
+        if distance > PICKUP_DISTANCE * MAZE_SCALE {
+            continue;
+        }
+
+        commands.entity(entity).despawn_recursive();
+
+        match pickup {
+            MazePickup::Polyhedron => roll.target += PI,
+            MazePickup::Token => {
+                let brick: Handle<Image> = asset_server.load(MAZE_WALL_PATH_BRICK);
+                let is_brick = materials
+                    .get(&maze_materials.wall)
+                    .and_then(|material| material.base_color_texture.as_ref())
+                    .is_some_and(|texture| texture.id() == brick.id());
+
+                let (wall, ceiling) = if is_brick {
+                    (MAZE_WALL_PATH_HEDGE, MAZE_CEILING_PATH_HEDGE)
+                } else {
+                    (MAZE_WALL_PATH_BRICK, MAZE_CEILING_PATH_BRICK)
+                };
+
+                if let Some(material) = materials.get_mut(&maze_materials.wall) {
+                    material.base_color_texture = Some(asset_server.load(wall));
+                }
+                if let Some(material) = materials.get_mut(&maze_materials.ceiling) {
+                    material.base_color_texture = Some(asset_server.load(ceiling));
+                }
+            }
+            MazePickup::Smiley => {
+                if fade.state == MazeFadeState::Idle {
+                    fade.start(MazeFadeState::Out);
+                }
+            }
+        }
+    }
+}
+
+/// Turns the camera over around its forward axis until it reaches the roll picked up from
+/// polyhedra. Walking keeps whatever roll the camera has, since it only ever turns around Z.
+fn camera_roll_system(
+    time: Res<Time>,
+    mut roll: ResMut<MazeRoll>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+) {
+    if roll.angle == roll.target {
+        return;
+    }
+
+    let Ok(mut camera_transform) = camera.get_single_mut() else {
+        return;
+    };
+
+    let step = (roll.target - roll.angle).clamp(
+        -time.delta_secs() * ROLL_SPEED,
+        time.delta_secs() * ROLL_SPEED,
+    );
+    roll.angle += step;
+    camera_transform.rotate_local_z(step);
+}
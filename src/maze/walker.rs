@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use bevy::prelude::*;
+use clap::ValueEnum;
+
+use super::{
+    adjacent_node, direction_order, is_open, next_valid_direction, opposite, MazeDirection,
+    MazeGraph, MazePassage, DIRECTION_LIST,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum MazeWalker {
+    /// Keeps a hand on the wall, eventually walking every corridor of a perfect maze.
+    #[default]
+    WallFollower,
+    /// Plans the shortest path to the exit and walks straight there.
+    Solver,
+    /// Prefers passages it hasn't taken yet, marking them as it goes (Trémaux's algorithm).
+    Explorer,
+}
+
+#[derive(Resource, Default)]
+pub(super) struct WalkerState {
+    /// Remaining cells of the solver's planned route, with the next cell last.
+    path: Vec<(i32, i32, i32)>,
+    /// Number of times the explorer has taken each passage, keyed by the cells it joins.
+    marks: BTreeMap<MazePassage, u32>,
+    visited: BTreeSet<(i32, i32, i32)>,
+    /// Cell walked from to reach the current one.
+    previous: Option<(i32, i32, i32)>,
+}
+
+impl WalkerState {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn has_visited(&self, cell: (i32, i32, i32)) -> bool {
+        self.visited.contains(&cell)
+    }
+
+    /// Chooses the cell to walk to after arriving at `current`, which is `current` itself if there
+    /// is nowhere to go.
+    pub fn next_cell(
+        &mut self,
+        walker: MazeWalker,
+        maze: &MazeGraph,
+        current: (i32, i32, i32),
+        facing: &MazeDirection,
+        goal: (i32, i32, i32),
+    ) -> (i32, i32, i32) {
+        self.visited.insert(current);
+        let edges = &maze[&current];
+
+        // The side arrived through, which is below after climbing stairs rather than behind.
+        let arrival = self
+            .previous
+            .replace(current)
+            .and_then(|previous| {
+                DIRECTION_LIST
+                    .iter()
+                    .find(|direction| adjacent_node(current, direction) == previous)
+            })
+            .copied()
+            .unwrap_or_else(|| opposite(facing));
+        let wall_following = next_valid_direction(&arrival, edges)
+            .map_or(current, |direction| adjacent_node(current, &direction));
+
+        match walker {
+            MazeWalker::WallFollower => wall_following,
+            MazeWalker::Solver => {
+                if self
+                    .path
+                    .last()
+                    .is_none_or(|next| !is_neighbor(maze, current, *next))
+                {
+                    self.path = shortest_path(maze, current, goal).unwrap_or_default();
+                    self.path.reverse();
+                    self.path.pop();
+                }
+
+                self.path.pop().unwrap_or(wall_following)
+            }
+            MazeWalker::Explorer => {
+                // Take the least used passage, preferring unvisited cells, then the wall-following
+                // order, to break ties.
+                let next = direction_order(&arrival)
+                    .iter()
+                    .filter(|direction| is_open(direction, edges))
+                    .map(|direction| adjacent_node(current, direction))
+                    .min_by_key(|next| {
+                        (
+                            self.marks
+                                .get(&passage(current, *next))
+                                .copied()
+                                .unwrap_or(0),
+                            self.visited.contains(next),
+                        )
+                    })
+                    .unwrap_or(wall_following);
+
+                if next != current {
+                    *self.marks.entry(passage(current, next)).or_default() += 1;
+                }
+                next
+            }
+        }
+    }
+}
+
+/// Cells along the shortest route from `from` to `to`, including both, found with a breadth-first
+/// search. Returns `None` if `to` can't be reached.
+pub fn shortest_path(
+    maze: &MazeGraph,
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+) -> Option<Vec<(i32, i32, i32)>> {
+    let mut previous = BTreeMap::from([(from, from)]);
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![to];
+            let mut cell = to;
+            while cell != from {
+                cell = previous[&cell];
+                path.push(cell);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let Some(edges) = maze.get(&current) else {
+            continue;
+        };
+
+        for direction in DIRECTION_LIST {
+            let next = adjacent_node(current, direction);
+            if is_open(direction, edges) && maze.contains_key(&next) {
+                previous.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    current
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Every cell that can be reached from `from`, including `from` itself.
+pub(super) fn reachable(maze: &MazeGraph, from: (i32, i32, i32)) -> BTreeSet<(i32, i32, i32)> {
+    let mut reached = BTreeSet::from([from]);
+    let mut unvisited = vec![from];
+
+    while let Some(current) = unvisited.pop() {
+        let Some(edges) = maze.get(&current) else {
+            continue;
+        };
+
+        for direction in DIRECTION_LIST {
+            let next = adjacent_node(current, direction);
+            if is_open(direction, edges) && maze.contains_key(&next) && reached.insert(next) {
+                unvisited.push(next);
+            }
+        }
+    }
+
+    reached
+}
+
+/// Whether `from` and `to` are neighbors with an opening between them.
+pub(super) fn is_neighbor(maze: &MazeGraph, from: (i32, i32, i32), to: (i32, i32, i32)) -> bool {
+    maze.get(&from).is_some_and(|edges| {
+        DIRECTION_LIST
+            .iter()
+            .any(|direction| adjacent_node(from, direction) == to && is_open(direction, edges))
+    })
+}
+
+/// Passage between `a` and `b`, the same whichever direction it is taken in.
+pub(super) fn passage(a: (i32, i32, i32), b: (i32, i32, i32)) -> MazePassage {
+    (a.min(b), a.max(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MazeLayout;
+
+    /// A perfect maze, with a dead end branching off the way from `S` to `E`.
+    const TREE: &str = "S.#..\n#.#.#\n#...E";
+    const START: (i32, i32, i32) = (0, 2, 0);
+    const GOAL: (i32, i32, i32) = (4, 0, 0);
+
+    fn tree() -> MazeGraph {
+        MazeLayout::parse(TREE).unwrap().maze().clone()
+    }
+
+    /// Cells walked through from `START`, including it, over `steps` steps.
+    fn walk(walker: MazeWalker, maze: &MazeGraph, steps: usize) -> Vec<(i32, i32, i32)> {
+        let mut state = WalkerState::default();
+        let mut cells = vec![START];
+
+        for _ in 0..steps {
+            let current = *cells.last().unwrap();
+            cells.push(state.next_cell(walker, maze, current, &MazeDirection::East, GOAL));
+        }
+
+        cells
+    }
+
+    #[test]
+    fn shortest_path_goes_straight_to_the_goal() {
+        let path = vec![
+            (0, 2, 0),
+            (1, 2, 0),
+            (1, 1, 0),
+            (1, 0, 0),
+            (2, 0, 0),
+            (3, 0, 0),
+            (4, 0, 0),
+        ];
+
+        assert_eq!(shortest_path(&tree(), START, GOAL), Some(path));
+        assert_eq!(shortest_path(&tree(), START, START), Some(vec![START]));
+    }
+
+    #[test]
+    fn cells_cut_off_are_not_reachable() {
+        let mut maze = tree();
+        let cells: BTreeSet<_> = maze.keys().copied().collect();
+        maze.insert((9, 9, 0), (false, false, false, false, false, false));
+
+        assert_eq!(reachable(&maze, START), cells);
+        assert_eq!(shortest_path(&maze, START, (9, 9, 0)), None);
+    }
+
+    #[test]
+    fn solver_follows_the_shortest_path() {
+        let maze = tree();
+        let path = shortest_path(&maze, START, GOAL).unwrap();
+
+        assert_eq!(walk(MazeWalker::Solver, &maze, path.len() - 1), path);
+    }
+
+    #[test]
+    fn wall_follower_walks_every_corridor_of_a_tree() {
+        let maze = tree();
+        // Every passage of a tree is walked once in each direction, ending back at the start.
+        let cells = walk(MazeWalker::WallFollower, &maze, 2 * (maze.len() - 1));
+
+        assert_eq!(
+            cells.iter().copied().collect::<BTreeSet<_>>(),
+            maze.keys().copied().collect()
+        );
+        assert_eq!(cells.last(), Some(&START));
+    }
+
+    #[test]
+    fn explorer_prefers_passages_it_has_not_taken() {
+        let maze = tree();
+        let junction = (3, 0, 0);
+        // Arriving from the west, having already been up the dead end to the north.
+        let state = || WalkerState {
+            previous: Some((2, 0, 0)),
+            marks: BTreeMap::from([(passage(junction, (3, 1, 0)), 1)]),
+            ..default()
+        };
+
+        let next = |walker| state().next_cell(walker, &maze, junction, &MazeDirection::East, GOAL);
+        assert_eq!(next(MazeWalker::WallFollower), (3, 1, 0));
+        assert_eq!(next(MazeWalker::Explorer), GOAL);
+    }
+
+    #[test]
+    fn explorer_takes_no_passage_more_than_twice_on_the_way_to_the_goal() {
+        let maze = tree();
+        let mut cells = walk(MazeWalker::Explorer, &maze, 4 * maze.len());
+        let arrival = cells.iter().position(|cell| *cell == GOAL);
+        cells.truncate(arrival.expect("the explorer never reached the goal") + 1);
+
+        for pair in cells.windows(2) {
+            let taken = cells
+                .windows(2)
+                .filter(|other| passage(other[0], other[1]) == passage(pair[0], pair[1]))
+                .count();
+            assert!(taken <= 2, "{pair:?} was taken {taken} times");
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use bevy_ratatui_camera::RatatuiCamera;
+use clap::{ArgMatches, Args, FromArgMatches};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    maze::{adjacent_node, MazeDirection, DIRECTION_LIST},
+    screensaver::positive_speed,
+    Screensaver,
+};
+
+/// Segments each pipe grows by per second, before `--speed`.
+const GROWTH_SPEED: f32 = 8.;
+const PIPE_RADIUS: f32 = 0.15;
+const JOINT_RADIUS: f32 = 0.22;
+/// Chance that a pipe carries straight on, when it can.
+const STRAIGHT_CHANCE: f64 = 0.7;
+/// Share of the grid that can fill up before everything is cleared and the pipes start over.
+const GRID_FILL: f32 = 0.6;
+/// Radians per second that the camera circles the grid at.
+const ORBIT_SPEED: f32 = 0.1;
+/// Distance of the camera from the center of the grid, in grid sizes.
+const ORBIT_DISTANCE: f32 = 1.6;
+const ORBIT_HEIGHT: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(PipeRng(ChaCha8Rng::from_entropy()))
+        .init_resource::<PipeGrid>()
+        .add_systems(Startup, pipes_setup_system)
+        .add_systems(Update, (pipe_growth_system, camera_orbit_system));
+}
+
+#[derive(Resource, Clone)]
+pub struct PipesSettings {
+    /// Cells along each side of the grid.
+    pub size: u32,
+    /// Multiplier for how fast the pipes grow.
+    pub speed: f32,
+    /// Pipes growing at the same time.
+    pub count: u32,
+}
+
+impl Default for PipesSettings {
+    fn default() -> Self {
+        Self {
+            size: 10,
+            speed: 1.,
+            count: 1,
+        }
+    }
+}
+
+#[derive(Args)]
+struct PipesArgs {
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u32).range(2..=64),
+        name = "CELLS",
+        help = "Cells along each side of the grid the pipes grow through."
+    )]
+    size: u32,
+
+    #[arg(
+        long,
+        default_value_t = 1.,
+        value_parser = positive_speed,
+        name = "SPEED",
+        help = "How fast the pipes grow, as a multiple of the usual speed."
+    )]
+    speed: f32,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=16),
+        name = "PIPES",
+        help = "Number of pipes growing at the same time."
+    )]
+    count: u32,
+}
+
+impl Screensaver for PipesSettings {
+    fn name(&self) -> &'static str {
+        "pipes"
+    }
+
+    fn about(&self) -> &'static str {
+        "Pipes growing through a 3D grid."
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        PipesArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        _subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let PipesArgs { size, speed, count } = PipesArgs::from_arg_matches(matches)?;
+
+        *self = Self { size, speed, count };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct PipeRng(ChaCha8Rng);
+
+/// Cells taken up by pipes so far, and the ends of the pipes that are still growing.
+#[derive(Resource, Default)]
+struct PipeGrid {
+    occupied: BTreeSet<(i32, i32, i32)>,
+    heads: Vec<PipeHead>,
+    /// Segments owed to the pipes since the last one was grown.
+    growth: f32,
+}
+
+impl PipeGrid {
+    fn is_filled(&self, size: u32) -> bool {
+        self.occupied.len() as f32 >= size.pow(3) as f32 * GRID_FILL
+    }
+}
+
+struct PipeHead {
+    cell: (i32, i32, i32),
+    direction: Option<MazeDirection>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Resource)]
+struct PipeMeshes {
+    segment: Handle<Mesh>,
+    joint: Handle<Mesh>,
+}
+
+/// Any piece of pipe, all of which are cleared away when the grid fills.
+#[derive(Component)]
+struct Pipe;
+
+fn pipes_setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut ambient: ResMut<AmbientLight>,
+) {
+    commands.spawn((
+        Msaa::Sample4,
+        RatatuiCamera::autoresize().with_autoresize_fn(|(w, h)| (w * 2, h * 2)),
+        Camera3d::default(),
+        Transform::default(),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::default().looking_to(Vec3::new(-1., -0.5, -2.), Vec3::Z),
+    ));
+    ambient.brightness = 300.;
+
+    commands.insert_resource(PipeMeshes {
+        segment: meshes.add(Cylinder::new(PIPE_RADIUS, 1.)),
+        joint: meshes.add(Sphere::new(JOINT_RADIUS)),
+    });
+}
+
+fn pipe_growth_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<PipesSettings>,
+    meshes: Res<PipeMeshes>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut grid: ResMut<PipeGrid>,
+    mut rng: ResMut<PipeRng>,
+    pipes: Query<Entity, With<Pipe>>,
+) {
+    // Once the grid has filled and the last pipes have got stuck, it's cleared for a fresh start.
+    if grid.heads.is_empty() && grid.is_filled(settings.size) {
+        for entity in &pipes {
+            commands.entity(entity).despawn();
+        }
+        grid.occupied.clear();
+    }
+
+    grid.growth += GROWTH_SPEED * settings.speed * time.delta_secs();
+
+    while grid.growth >= 1. {
+        grid.growth -= 1.;
+
+        // Pipes that got stuck are replaced by new ones from wherever there is room.
+        while grid.heads.len() < settings.count as usize && !grid.is_filled(settings.size) {
+            let Some(cell) = free_cell(&mut rng, &grid, settings.size) else {
+                break;
+            };
+
+            let material = materials.add(StandardMaterial {
+                base_color: Color::hsl(rng.gen_range(0.0..360.), 0.8, 0.5),
+                perceptual_roughness: 0.3,
+                metallic: 0.2,
+                ..default()
+            });
+            spawn_joint(&mut commands, &meshes, &material, cell, settings.size);
+            grid.occupied.insert(cell);
+            grid.heads.push(PipeHead {
+                cell,
+                direction: None,
+                material,
+            });
+        }
+
+        if grid.heads.is_empty() {
+            grid.growth = 0.;
+            break;
+        }
+
+        let PipeGrid {
+            ref mut occupied,
+            ref mut heads,
+            ..
+        } = *grid;
+
+        heads.retain_mut(|head| {
+            let open: Vec<MazeDirection> = DIRECTION_LIST
+                .iter()
+                .copied()
+                .filter(|direction| {
+                    let next = adjacent_node(head.cell, direction);
+                    in_grid(next, settings.size) && !occupied.contains(&next)
+                })
+                .collect();
+
+            let direction = match head.direction {
+                Some(direction) if open.contains(&direction) && rng.gen_bool(STRAIGHT_CHANCE) => {
+                    direction
+                }
+                _ => match open.choose(&mut rng.0) {
+                    Some(direction) => *direction,
+                    None => {
+                        // Nowhere left to go, so the pipe ends here.
+                        spawn_joint(
+                            &mut commands,
+                            &meshes,
+                            &head.material,
+                            head.cell,
+                            settings.size,
+                        );
+                        return false;
+                    }
+                },
+            };
+
+            if head.direction.is_some_and(|previous| previous != direction) {
+                spawn_joint(
+                    &mut commands,
+                    &meshes,
+                    &head.material,
+                    head.cell,
+                    settings.size,
+                );
+            }
+
+            let next = adjacent_node(head.cell, &direction);
+            let (from, to) = (
+                cell_position(head.cell, settings.size),
+                cell_position(next, settings.size),
+            );
+            commands.spawn((
+                Pipe,
+                Mesh3d(meshes.segment.clone()),
+                MeshMaterial3d(head.material.clone()),
+                Transform::from_translation(from.lerp(to, 0.5))
+                    .with_rotation(Quat::from_rotation_arc(Vec3::Y, (to - from).normalize())),
+            ));
+
+            occupied.insert(next);
+            head.cell = next;
+            head.direction = Some(direction);
+            true
+        });
+    }
+}
+
+fn camera_orbit_system(
+    time: Res<Time>,
+    settings: Res<PipesSettings>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+) {
+    let angle = time.elapsed_secs() * ORBIT_SPEED;
+    let distance = settings.size as f32 * ORBIT_DISTANCE;
+    let translation = Vec3::new(angle.cos(), angle.sin(), ORBIT_HEIGHT) * distance;
+
+    for mut transform in &mut camera {
+        *transform = Transform::from_translation(translation).looking_at(Vec3::ZERO, Vec3::Z);
+    }
+}
+
+fn spawn_joint(
+    commands: &mut Commands,
+    meshes: &PipeMeshes,
+    material: &Handle<StandardMaterial>,
+    cell: (i32, i32, i32),
+    size: u32,
+) {
+    commands.spawn((
+        Pipe,
+        Mesh3d(meshes.joint.clone()),
+        MeshMaterial3d(material.clone()),
+        Transform::from_translation(cell_position(cell, size)),
+    ));
+}
+
+/// A random cell that no pipe has been through yet, if there are any.
+fn free_cell(rng: &mut PipeRng, grid: &PipeGrid, size: u32) -> Option<(i32, i32, i32)> {
+    let size = size as i32;
+    let free = size.pow(3) as usize - grid.occupied.len();
+    if free == 0 {
+        return None;
+    }
+
+    let index = rng.gen_range(0..free);
+    (0..size)
+        .flat_map(|x| (0..size).flat_map(move |y| (0..size).map(move |z| (x, y, z))))
+        .filter(|cell| !grid.occupied.contains(cell))
+        .nth(index)
+}
+
+fn in_grid((x, y, z): (i32, i32, i32), size: u32) -> bool {
+    let size = size as i32;
+    (0..size).contains(&x) && (0..size).contains(&y) && (0..size).contains(&z)
+}
+
+/// Center of `cell`, with the grid centered on the origin.
+fn cell_position((x, y, z): (i32, i32, i32), size: u32) -> Vec3 {
+    Vec3::new(x as f32, y as f32, z as f32) - (size as f32 - 1.) / 2.
+}
@@ -0,0 +1,522 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use clap::{error::ErrorKind, ArgMatches, Command};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng,
+};
+
+use crate::{
+    state, BubblesSettings, LogoSettings, MatrixSettings, MazeSettings, PipesSettings,
+    StarfieldSettings, ToastersSettings,
+};
+
+const PREVIOUS_CHOICE_STATE: &str = "previous_screensaver";
+
+/// A screensaver that [`AppPlugin`](crate::AppPlugin) can run. The built in variants implement it,
+/// and other crates can implement it to add their own screensavers to a [`ScreensaverRegistry`].
+pub trait Screensaver: Send + Sync + 'static {
+    /// Name that picks the screensaver on the command line, like `maze`.
+    fn name(&self) -> &'static str;
+
+    /// One line description, shown in `--help`.
+    fn about(&self) -> &'static str;
+
+    /// Variations that can follow the name on the command line, like `ttysvr maze hedge`.
+    fn subvariants(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Adds the screensaver's own options to its subcommand.
+    fn args(&self, command: Command) -> Command {
+        command
+    }
+
+    /// Replaces the screensaver's settings with the ones picked on the command line, with
+    /// `subvariant` set if one was given after the name.
+    fn parse_args(
+        &mut self,
+        _subvariant: Option<&str>,
+        _matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        Ok(())
+    }
+
+    /// Adds the screensaver's resources and systems to the app.
+    fn build(&self, app: &mut App);
+}
+
+/// Screensavers to choose between, each with a subcommand of its own on the command line.
+///
+/// Random selection draws from every subvariant of every screensaver, named like `maze:hedge`
+/// (or just `bubbles` for screensavers without subvariants). Wherever a choice is named, the
+/// screensaver name alone stands for all of its subvariants.
+pub struct ScreensaverRegistry {
+    screensavers: Vec<Box<dyn Screensaver>>,
+    weights: Vec<(String, f64)>,
+    random_from: Vec<String>,
+    excluded: Vec<String>,
+    no_repeat: bool,
+}
+
+impl Default for ScreensaverRegistry {
+    /// The built in screensavers.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(BubblesSettings::default())
+            .register(LogoSettings::default())
+            .register(MazeSettings::default())
+            .register(StarfieldSettings::default())
+            .register(MatrixSettings::default())
+            .register(PipesSettings::default())
+            .register(ToastersSettings::default());
+        registry
+    }
+}
+
+impl ScreensaverRegistry {
+    pub fn empty() -> Self {
+        Self {
+            screensavers: vec![],
+            weights: vec![],
+            random_from: vec![],
+            excluded: vec![],
+            no_repeat: false,
+        }
+    }
+
+    /// Adds a screensaver, replacing any registered under the same name.
+    pub fn register(&mut self, screensaver: impl Screensaver) -> &mut Self {
+        self.screensavers
+            .retain(|registered| registered.name() != screensaver.name());
+        self.screensavers.push(Box::new(screensaver));
+        self
+    }
+
+    /// Makes random selection pick `choice` `weight` times as often as a choice without a weight.
+    /// A weight for a single subvariant wins over one for the whole screensaver.
+    pub fn weight(&mut self, choice: impl Into<String>, weight: f64) -> &mut Self {
+        self.weights.push((choice.into(), weight));
+        self
+    }
+
+    /// Limits random selection to `choice`, along with any others given the same way.
+    pub fn random_from(&mut self, choice: impl Into<String>) -> &mut Self {
+        self.random_from.push(choice.into());
+        self
+    }
+
+    /// Leaves `choice` out of random selection. It can still be picked on the command line.
+    pub fn exclude(&mut self, choice: impl Into<String>) -> &mut Self {
+        self.excluded.push(choice.into());
+        self
+    }
+
+    /// Stops random selection from picking the same choice twice in a row, remembering the
+    /// previous pick between runs.
+    pub fn no_repeat(&mut self, no_repeat: bool) -> &mut Self {
+        self.no_repeat = no_repeat;
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Screensaver> {
+        self.screensavers.iter().map(Box::as_ref)
+    }
+
+    /// Every choice random selection could make, ignoring weights and exclusions.
+    pub fn choices(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter()
+            .flat_map(choices)
+            .map(|(name, subvariant)| choice_name(name, subvariant))
+    }
+
+    /// Subcommands for every registered screensaver, as they appear on the command line.
+    pub fn commands(&self) -> impl Iterator<Item = Command> + '_ {
+        self.iter().map(subcommand)
+    }
+
+    /// Adds a subcommand to `command` for every registered screensaver.
+    pub fn command(&self, command: Command) -> Command {
+        command.subcommands(self.commands())
+    }
+
+    /// Takes the screensaver picked in `matches`, parsed from a command built by
+    /// [`ScreensaverRegistry::command`], set up with its options. Picks one at random if none was.
+    pub fn parse(
+        self,
+        matches: &ArgMatches,
+        rng: &mut impl Rng,
+    ) -> Result<Box<dyn Screensaver>, clap::Error> {
+        let Some((name, matches)) = matches.subcommand() else {
+            return self.random(rng);
+        };
+
+        let mut screensaver = self
+            .screensavers
+            .into_iter()
+            .find(|screensaver| screensaver.name() == name)
+            .ok_or_else(|| {
+                clap::Error::raw(
+                    ErrorKind::InvalidSubcommand,
+                    format!("no screensaver named {name}\n"),
+                )
+            })?;
+        screensaver.parse_args(matches.subcommand_name(), matches)?;
+
+        Ok(screensaver)
+    }
+
+    /// Takes a screensaver at random, with its default options, out of the choices that are
+    /// included and not excluded, and according to their weights.
+    pub fn random(mut self, rng: &mut impl Rng) -> Result<Box<dyn Screensaver>, clap::Error> {
+        let known: Vec<String> = self.choices().collect();
+        for choice in self
+            .weights
+            .iter()
+            .map(|(choice, _)| choice)
+            .chain(&self.random_from)
+            .chain(&self.excluded)
+        {
+            if !known.iter().any(|known| matches_choice(choice, known)) {
+                return Err(clap::Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "no screensaver named {choice}, expected one of {}\n",
+                        known.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        let mut candidates: Vec<(usize, Option<&'static str>, String)> = self
+            .screensavers
+            .iter()
+            .enumerate()
+            .flat_map(|(index, screensaver)| {
+                choices(screensaver.as_ref()).map(move |(name, subvariant)| {
+                    (index, subvariant, choice_name(name, subvariant))
+                })
+            })
+            .filter(|(_, _, name)| {
+                let included = self.random_from.is_empty()
+                    || self
+                        .random_from
+                        .iter()
+                        .any(|choice| matches_choice(choice, name));
+                let excluded = self
+                    .excluded
+                    .iter()
+                    .any(|choice| matches_choice(choice, name));
+
+                included && !excluded
+            })
+            .collect();
+
+        // The previous pick is only left out if there is something else to pick instead.
+        if let Some(previous) = self
+            .no_repeat
+            .then(|| state::load(PREVIOUS_CHOICE_STATE))
+            .flatten()
+        {
+            if candidates.iter().any(|(_, _, name)| *name != previous) {
+                candidates.retain(|(_, _, name)| *name != previous);
+            }
+        }
+
+        let distribution = WeightedIndex::new(
+            candidates
+                .iter()
+                .map(|(_, _, name)| self.choice_weight(name)),
+        )
+        .map_err(|_| {
+            clap::Error::raw(
+                ErrorKind::MissingSubcommand,
+                "no screensavers left to pick from at random\n",
+            )
+        })?;
+        let (index, subvariant, ref name) = candidates[distribution.sample(rng)];
+
+        if self.no_repeat {
+            let _ = state::save(PREVIOUS_CHOICE_STATE, name);
+        }
+
+        let mut screensaver = self.screensavers.swap_remove(index);
+        let matches = subcommand(screensaver.as_ref())
+            .try_get_matches_from([screensaver.name()].into_iter().chain(subvariant))?;
+        screensaver.parse_args(subvariant, &matches)?;
+
+        Ok(screensaver)
+    }
+
+    /// Weight of a choice like `maze:hedge`, preferring one given for the subvariant over one
+    /// given for the whole screensaver.
+    fn choice_weight(&self, name: &str) -> f64 {
+        let weight = |choice: &str| {
+            self.weights
+                .iter()
+                .rev()
+                .find(|(weighted, _)| weighted == choice)
+                .map(|(_, weight)| *weight)
+        };
+
+        weight(name)
+            .or_else(|| weight(name.split(':').next().unwrap_or(name)))
+            .unwrap_or(1.)
+    }
+}
+
+/// The screensaver's name with each of its subvariants, or just its name if it has none.
+fn choices(
+    screensaver: &dyn Screensaver,
+) -> impl Iterator<Item = (&'static str, Option<&'static str>)> {
+    let name = screensaver.name();
+    let subvariants = screensaver.subvariants();
+
+    let whole = subvariants.is_empty().then_some((name, None));
+    whole.into_iter().chain(
+        subvariants
+            .iter()
+            .map(move |subvariant| (name, Some(*subvariant))),
+    )
+}
+
+fn choice_name(name: &str, subvariant: Option<&str>) -> String {
+    match subvariant {
+        Some(subvariant) => format!("{name}:{subvariant}"),
+        None => name.into(),
+    }
+}
+
+/// Whether `choice`, as given by the user, covers the choice named `name`.
+fn matches_choice(choice: &str, name: &str) -> bool {
+    choice == name || name.split(':').next() == Some(choice)
+}
+
+/// Subcommand for `screensaver`, with a further subcommand for each of its subvariants.
+fn subcommand(screensaver: &dyn Screensaver) -> Command {
+    let command = Command::new(screensaver.name()).about(screensaver.about());
+
+    screensaver
+        .args(command)
+        .subcommands(screensaver.subvariants().iter().map(Command::new))
+}
+
+/// Value parser for options that name a file, resolving it to an absolute path.
+pub(crate) fn existing_file(value: &str) -> Result<PathBuf, String> {
+    fs::canonicalize(value).map_err(|error| format!("could not open {value}: {error}"))
+}
+
+/// Value parser for speeds, which have to be above zero.
+pub(crate) fn positive_speed(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(speed) if speed.is_finite() && speed > 0. => Ok(speed),
+        _ => Err(format!("expected a speed above 0, got {value}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeSet,
+        env,
+        sync::{Arc, Mutex},
+    };
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Choice name of the last screensaver set up by [`ScreensaverRegistry::random`].
+    type Picked = Arc<Mutex<Option<String>>>;
+
+    struct TestSaver {
+        name: &'static str,
+        subvariants: &'static [&'static str],
+        picked: Picked,
+    }
+
+    impl Screensaver for TestSaver {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn about(&self) -> &'static str {
+            "Only for tests."
+        }
+
+        fn subvariants(&self) -> &'static [&'static str] {
+            self.subvariants
+        }
+
+        fn parse_args(
+            &mut self,
+            subvariant: Option<&str>,
+            _matches: &ArgMatches,
+        ) -> Result<(), clap::Error> {
+            *self.picked.lock().unwrap() = Some(choice_name(self.name, subvariant));
+            Ok(())
+        }
+
+        fn build(&self, _app: &mut App) {}
+    }
+
+    /// Registry of `plain`, without subvariants, and `varied`, with `varied:a` and `varied:b`.
+    fn registry(picked: &Picked) -> ScreensaverRegistry {
+        let mut registry = ScreensaverRegistry::empty();
+        registry
+            .register(TestSaver {
+                name: "plain",
+                subvariants: &[],
+                picked: picked.clone(),
+            })
+            .register(TestSaver {
+                name: "varied",
+                subvariants: &["a", "b"],
+                picked: picked.clone(),
+            });
+        registry
+    }
+
+    /// Choices picked over repeated random selections from registries set up by `setup`.
+    fn picks(setup: impl Fn(&mut ScreensaverRegistry)) -> Vec<String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let picked = Picked::default();
+
+        (0..100)
+            .map(|_| {
+                let mut registry = registry(&picked);
+                setup(&mut registry);
+                registry.random(&mut rng).unwrap();
+                picked.lock().unwrap().take().unwrap()
+            })
+            .collect()
+    }
+
+    fn distinct(picks: Vec<String>) -> BTreeSet<String> {
+        picks.into_iter().collect()
+    }
+
+    fn random_error(setup: impl Fn(&mut ScreensaverRegistry)) -> clap::Error {
+        let mut registry = registry(&Picked::default());
+        setup(&mut registry);
+        match registry.random(&mut ChaCha8Rng::seed_from_u64(0)) {
+            Ok(screensaver) => panic!("expected an error, picked {}", screensaver.name()),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn random_picks_every_subvariant() {
+        assert_eq!(
+            distinct(picks(|_| {})),
+            BTreeSet::from(["plain".into(), "varied:a".into(), "varied:b".into()])
+        );
+    }
+
+    #[test]
+    fn excluding_a_screensaver_excludes_its_subvariants() {
+        assert_eq!(
+            distinct(picks(|registry| {
+                registry.exclude("varied");
+            })),
+            BTreeSet::from(["plain".into()])
+        );
+    }
+
+    #[test]
+    fn excluding_a_subvariant_keeps_the_others() {
+        assert_eq!(
+            distinct(picks(|registry| {
+                registry.exclude("varied:a");
+            })),
+            BTreeSet::from(["plain".into(), "varied:b".into()])
+        );
+    }
+
+    #[test]
+    fn random_from_narrows_the_choices() {
+        assert_eq!(
+            distinct(picks(|registry| {
+                registry.random_from("varied");
+            })),
+            BTreeSet::from(["varied:a".into(), "varied:b".into()])
+        );
+        assert_eq!(
+            distinct(picks(|registry| {
+                registry.random_from("plain").random_from("varied:b");
+            })),
+            BTreeSet::from(["plain".into(), "varied:b".into()])
+        );
+    }
+
+    #[test]
+    fn a_zero_weight_is_never_picked() {
+        assert_eq!(
+            distinct(picks(|registry| {
+                registry.weight("varied", 0.).weight("varied:b", 1.);
+            })),
+            BTreeSet::from(["plain".into(), "varied:b".into()])
+        );
+    }
+
+    #[test]
+    fn no_repeat_never_picks_the_previous_choice() {
+        env::set_var(
+            "XDG_STATE_HOME",
+            env::temp_dir().join(format!("ttysvr-test-{}", std::process::id())),
+        );
+
+        let repeated = picks(|registry| {
+            registry.no_repeat(true);
+        });
+        for pair in repeated.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+
+        // With nothing else to pick, the previous choice is picked again.
+        let only = picks(|registry| {
+            registry.no_repeat(true).random_from("plain");
+        });
+        assert_eq!(distinct(only), BTreeSet::from(["plain".into()]));
+    }
+
+    #[test]
+    fn nothing_to_pick_is_an_error() {
+        let error = random_error(|registry| {
+            registry.exclude("plain").exclude("varied");
+        });
+        assert_eq!(error.kind(), ErrorKind::MissingSubcommand);
+
+        let error = random_error(|registry| {
+            registry.weight("plain", 0.).weight("varied", 0.);
+        });
+        assert_eq!(error.kind(), ErrorKind::MissingSubcommand);
+
+        let error = ScreensaverRegistry::empty()
+            .random(&mut ChaCha8Rng::seed_from_u64(0))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingSubcommand);
+    }
+
+    #[test]
+    fn unknown_choices_are_an_error() {
+        for setup in [
+            |registry: &mut ScreensaverRegistry| {
+                registry.exclude("missing");
+            },
+            |registry: &mut ScreensaverRegistry| {
+                registry.random_from("varied:c");
+            },
+            |registry: &mut ScreensaverRegistry| {
+                registry.weight("plain:a", 2.);
+            },
+        ] {
+            let error = random_error(setup);
+            assert_eq!(error.kind(), ErrorKind::InvalidValue);
+        }
+    }
+}
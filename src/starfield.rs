@@ -0,0 +1,281 @@
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_ratatui_camera::RatatuiCamera;
+use clap::{ArgMatches, Args, FromArgMatches};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{screensaver::positive_speed, Screensaver};
+
+/// Stars live in a box this far ahead of the camera, and this far out to each side.
+const FIELD_DEPTH: f32 = 120.;
+const FIELD_RADIUS: f32 = 40.;
+const STAR_SIZE: f32 = 0.2;
+/// Number of materials that stars without a chosen color are shared between.
+const STAR_TINTS: usize = 8;
+/// Seconds for hyperspace to spool up from warp speed to full speed.
+const HYPERSPACE_SPOOL_SECONDS: f32 = 4.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(StarRng(ChaCha8Rng::from_entropy()))
+        .add_systems(Startup, starfield_setup_system)
+        .add_systems(Update, (star_movement_system, camera_roll_system));
+}
+
+#[derive(Resource, Clone)]
+pub struct StarfieldSettings {
+    pub style: StarfieldStyle,
+    /// Multiplier for how fast the stars stream past.
+    pub speed: f32,
+    /// Number of stars.
+    pub density: u32,
+    /// Color of every star, instead of a mix of whites, blues and yellows.
+    pub color: Option<Srgba>,
+}
+
+impl Default for StarfieldSettings {
+    fn default() -> Self {
+        Self {
+            style: StarfieldStyle::Warp,
+            speed: 1.,
+            density: 400,
+            color: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StarfieldStyle {
+    /// Stars rush past, each pulling a short trail.
+    Warp,
+    /// Stars float slowly by while the view gently rolls.
+    Drift,
+    /// Speeds up from warp into long streaks of light.
+    Hyperspace,
+}
+
+impl StarfieldStyle {
+    /// Top speed of the stars, in units per second.
+    fn speed(&self) -> f32 {
+        match self {
+            StarfieldStyle::Warp => 24.,
+            StarfieldStyle::Drift => 3.,
+            StarfieldStyle::Hyperspace => 120.,
+        }
+    }
+
+    /// How far back each star's trail reaches, in seconds of movement.
+    fn trail(&self) -> f32 {
+        match self {
+            StarfieldStyle::Warp => 0.06,
+            StarfieldStyle::Drift => 0.,
+            StarfieldStyle::Hyperspace => 0.3,
+        }
+    }
+
+    /// Speed the view rolls at, in radians per second.
+    fn roll(&self) -> f32 {
+        match self {
+            StarfieldStyle::Drift => 0.05,
+            _ => 0.,
+        }
+    }
+}
+
+#[derive(Args)]
+struct StarfieldArgs {
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 1.,
+        value_parser = positive_speed,
+        name = "SPEED",
+        help = "How fast the stars stream past, as a multiple of the usual speed."
+    )]
+    speed: f32,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 400,
+        value_parser = clap::value_parser!(u32).range(1..),
+        name = "STARS",
+        help = "Number of stars."
+    )]
+    density: u32,
+
+    #[arg(
+        long,
+        global = true,
+        value_parser = star_color,
+        name = "STAR COLOR",
+        help = "HEX COLOR of every star, instead of a mix of whites, blues and yellows."
+    )]
+    color: Option<Srgba>,
+}
+
+impl Screensaver for StarfieldSettings {
+    fn name(&self) -> &'static str {
+        "starfield"
+    }
+
+    fn about(&self) -> &'static str {
+        "Stars streaming toward you at warp speed."
+    }
+
+    fn subvariants(&self) -> &'static [&'static str] {
+        &["warp", "drift", "hyperspace"]
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        StarfieldArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let StarfieldArgs {
+            speed,
+            density,
+            color,
+        } = StarfieldArgs::from_arg_matches(matches)?;
+
+        *self = Self {
+            style: match subvariant {
+                Some("drift") => StarfieldStyle::Drift,
+                Some("hyperspace") => StarfieldStyle::Hyperspace,
+                _ => StarfieldStyle::Warp,
+            },
+            speed,
+            density,
+            color,
+        };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+#[derive(Component)]
+struct Star;
+
+#[derive(Resource, Deref, DerefMut)]
+struct StarRng(ChaCha8Rng);
+
+fn starfield_setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut rng: ResMut<StarRng>,
+    settings: Res<StarfieldSettings>,
+) {
+    commands.spawn((
+        RatatuiCamera::autoresize(),
+        Camera3d::default(),
+        Transform::default(),
+    ));
+
+    let mesh = meshes.add(Cuboid::from_length(STAR_SIZE));
+    let colors: Vec<Color> = match settings.color {
+        Some(color) => vec![color.into()],
+        None => (0..STAR_TINTS)
+            .map(|_| {
+                // Mostly white, with a few blue and yellow stars.
+                let hue = if rng.gen_bool(0.5) { 220. } else { 50. };
+                Color::hsl(hue, rng.gen_range(0.0..0.6), rng.gen_range(0.75..1.0))
+            })
+            .collect(),
+    };
+    let materials: Vec<_> = colors
+        .into_iter()
+        .map(|color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            })
+        })
+        .collect();
+
+    for index in 0..settings.density {
+        // Spread out through the whole field to begin with, rather than arriving all at once.
+        let translation = star_position(&mut rng, -FIELD_DEPTH..0.);
+        commands.spawn((
+            Star,
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(materials[index as usize % materials.len()].clone()),
+            Transform::from_translation(translation),
+        ));
+    }
+}
+
+fn star_movement_system(
+    time: Res<Time>,
+    settings: Res<StarfieldSettings>,
+    mut rng: ResMut<StarRng>,
+    mut stars: Query<&mut Transform, With<Star>>,
+) {
+    let spool = match settings.style {
+        StarfieldStyle::Hyperspace => {
+            (time.elapsed_secs() / HYPERSPACE_SPOOL_SECONDS).clamp(0., 1.)
+        }
+        _ => 1.,
+    };
+    let top_speed = settings.style.speed();
+    let speed = match settings.style {
+        StarfieldStyle::Hyperspace => {
+            let warp = StarfieldStyle::Warp.speed();
+            warp + (top_speed - warp) * spool * spool
+        }
+        _ => top_speed,
+    } * settings.speed;
+    let trail = settings.style.trail() * spool;
+
+    for mut transform in &mut stars {
+        // Where the star itself is, ahead of the middle of its trail.
+        let mut position = transform.translation;
+        position.z += trail_offset(transform.scale.z) + speed * time.delta_secs();
+
+        // Stars that have flown past the camera start again at the far end of the field.
+        if position.z > 0. {
+            position = star_position(&mut rng, -FIELD_DEPTH..-FIELD_DEPTH * 0.9);
+        }
+
+        // Stretching each star back along its path gives it a motion blurred trail, which is
+        // moved back by half its length so that it only stretches out behind the star.
+        transform.scale.z = (speed * trail / STAR_SIZE).max(1.);
+        transform.translation = position - Vec3::Z * trail_offset(transform.scale.z);
+    }
+}
+
+/// How far the middle of a star stretched to `scale` lies behind the star.
+fn trail_offset(scale: f32) -> f32 {
+    (scale - 1.) * STAR_SIZE / 2.
+}
+
+fn camera_roll_system(
+    time: Res<Time>,
+    settings: Res<StarfieldSettings>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+) {
+    for mut transform in &mut camera {
+        transform.rotate_local_z(settings.style.roll() * time.delta_secs());
+    }
+}
+
+fn star_position(rng: &mut StarRng, depth: Range<f32>) -> Vec3 {
+    Vec3::new(
+        rng.gen_range(-FIELD_RADIUS..FIELD_RADIUS),
+        rng.gen_range(-FIELD_RADIUS..FIELD_RADIUS),
+        rng.gen_range(depth),
+    )
+}
+
+fn star_color(value: &str) -> Result<Srgba, String> {
+    Srgba::hex(value).map_err(|_| format!("expected a hex color like ffcc00, got {value}"))
+}
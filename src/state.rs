@@ -0,0 +1,37 @@
+use std::{env, fs, io, path::PathBuf};
+
+/// Directory for small pieces of state that should survive between runs, following the XDG base
+/// directory spec where it applies (`$XDG_STATE_HOME/ttysvr`, `~/.local/state/ttysvr`).
+pub fn state_dir() -> Option<PathBuf> {
+    if let Some(state_home) = env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(state_home).join("ttysvr"));
+    }
+
+    if let Some(local_app_data) = env::var_os("LOCALAPPDATA").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(local_app_data).join("ttysvr"));
+    }
+
+    env::var_os("HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(|home| PathBuf::from(home).join(".local/state/ttysvr"))
+}
+
+/// Reads the state stored under `name`, or `None` if it has never been written.
+pub fn load(name: &str) -> Option<String> {
+    let path = state_dir()?.join(name);
+    fs::read_to_string(path)
+        .ok()
+        .map(|contents| contents.trim().into())
+}
+
+pub fn save(name: &str, contents: &str) -> io::Result<()> {
+    let Some(dir) = state_dir() else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no state directory available",
+        ));
+    };
+
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(name), contents)
+}
@@ -0,0 +1,50 @@
+use std::io;
+use std::panic;
+
+use ratatui::crossterm::event::{DisableMouseCapture, PopKeyboardEnhancementFlags};
+use ratatui::crossterm::terminal::{self, LeaveAlternateScreen};
+use ratatui::crossterm::{cursor, execute};
+
+/// Puts the terminal back the way it was before the screensaver if ttysvr panics or is killed,
+/// instead of leaving the shell in raw mode on the alternate screen. Replaces the process's panic
+/// hook and handles its signals, so it is only for when ttysvr is the whole program.
+pub(super) fn restore_on_exit() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+
+    #[cfg(unix)]
+    restore_on_signals();
+}
+
+/// Undoes everything the screensaver does to the terminal. Harmless if some of it was never done.
+fn restore() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        PopKeyboardEnhancementFlags,
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        cursor::Show
+    );
+}
+
+#[cfg(unix)]
+fn restore_on_signals() {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let Ok(mut signals) = Signals::new([SIGTERM, SIGHUP, SIGINT]) else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            restore();
+            // The usual exit status for being killed by a signal.
+            std::process::exit(128 + signal);
+        }
+    });
+}
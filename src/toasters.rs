@@ -0,0 +1,355 @@
+use std::path::PathBuf;
+
+use bevy::asset::{AssetPath, LoadState};
+use bevy::prelude::*;
+use bevy_ratatui_camera::RatatuiCamera;
+use clap::{ArgMatches, Args, FromArgMatches};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    common::Viewport,
+    file_asset_path,
+    screensaver::{existing_file, positive_speed},
+    ExitMessage, Screensaver,
+};
+
+pub const TOASTERS_PATH_TOASTER: &str = "embedded://ttysvr/../assets/toaster.png";
+pub const TOASTERS_PATH_TOAST: &str = "embedded://ttysvr/../assets/toast.png";
+
+const ORTHO_SCALING: f32 = 0.5;
+/// Longest side of a flyer in the nearest layer.
+const FLYER_SIZE: f32 = 16.;
+/// Speed of a flyer in the nearest layer, before `--speed`.
+const FLYER_SPEED: f32 = 16.;
+/// Everything flies right to left and gently downwards.
+const FLYER_HEADING: Vec2 = Vec2::new(-2., -1.);
+const FRAME_SECONDS: f32 = 0.12;
+/// Layers from the farthest away, which are smaller, slower and dimmer, to the nearest.
+const LAYERS: u32 = 3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(ToasterRng(ChaCha8Rng::from_entropy()))
+        .add_systems(Startup, toasters_setup_system)
+        .add_systems(
+            Update,
+            (
+                sheet_layout_system,
+                flyer_spawn_system,
+                flyer_movement_system,
+                flyer_animation_system,
+            )
+                .chain(),
+        );
+}
+
+#[derive(Resource, Clone)]
+pub struct ToastersSettings {
+    /// Sheets that the flyers take turns being drawn from.
+    pub sheets: Vec<ToasterSheet>,
+    /// Number of flyers on the screen.
+    pub count: u32,
+    /// Multiplier for how fast the flyers go.
+    pub speed: f32,
+}
+
+impl Default for ToastersSettings {
+    fn default() -> Self {
+        let toaster = ToasterSheet {
+            path: TOASTERS_PATH_TOASTER.into(),
+            frames: 4,
+        };
+        let toast = ToasterSheet {
+            path: TOASTERS_PATH_TOAST.into(),
+            frames: 1,
+        };
+
+        Self {
+            // Two toasters for every slice of toast.
+            sheets: vec![toaster.clone(), toaster, toast],
+            count: 12,
+            speed: 1.,
+        }
+    }
+}
+
+/// Image with the frames of an animation side by side, all the same size.
+#[derive(Clone)]
+pub struct ToasterSheet {
+    pub path: AssetPath<'static>,
+    pub frames: u32,
+}
+
+#[derive(Args)]
+struct ToastersArgs {
+    #[arg(
+        long,
+        default_value_t = 12,
+        value_parser = clap::value_parser!(u32).range(1..=200),
+        name = "FLYERS",
+        help = "Number of toasters and toast on the screen."
+    )]
+    count: u32,
+
+    #[arg(
+        long,
+        default_value_t = 1.,
+        value_parser = positive_speed,
+        name = "SPEED",
+        help = "How fast they fly, as a multiple of the usual speed."
+    )]
+    speed: f32,
+
+    #[arg(
+        long,
+        name = "SHEET",
+        value_parser = existing_file,
+        help = "Fly sprites from the provided SHEET instead of toasters and toast. Can be repeated."
+    )]
+    sheet: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..=64),
+        requires = "SHEET",
+        name = "FRAMES",
+        help = "Number of animation FRAMES side by side in each SHEET."
+    )]
+    frames: u32,
+}
+
+impl Screensaver for ToastersSettings {
+    fn name(&self) -> &'static str {
+        "toasters"
+    }
+
+    fn about(&self) -> &'static str {
+        "Flying toasters on parade."
+    }
+
+    fn args(&self, command: clap::Command) -> clap::Command {
+        ToastersArgs::augment_args(command)
+    }
+
+    fn parse_args(
+        &mut self,
+        _subvariant: Option<&str>,
+        matches: &ArgMatches,
+    ) -> Result<(), clap::Error> {
+        let ToastersArgs {
+            count,
+            speed,
+            sheet,
+            frames,
+        } = ToastersArgs::from_arg_matches(matches)?;
+
+        let sheets = if sheet.is_empty() {
+            Self::default().sheets
+        } else {
+            sheet
+                .iter()
+                .map(|path| ToasterSheet {
+                    path: file_asset_path(path),
+                    frames,
+                })
+                .collect()
+        };
+
+        *self = Self {
+            sheets,
+            count,
+            speed,
+        };
+        Ok(())
+    }
+
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone()).add_plugins(plugin);
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct ToasterRng(ChaCha8Rng);
+
+/// Loaded sheets, each with the layout of its frames once the image is loaded and its size known.
+#[derive(Resource)]
+struct ToasterSheets(Vec<LoadedSheet>);
+
+struct LoadedSheet {
+    image: Handle<Image>,
+    frames: u32,
+    layout: Option<Handle<TextureAtlasLayout>>,
+    /// Size of its flyers in the nearest layer, in the proportions of one frame.
+    size: Vec2,
+}
+
+#[derive(Component)]
+struct Flyer {
+    velocity: Vec2,
+    frames: usize,
+    frame_timer: Timer,
+}
+
+fn toasters_setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<ToastersSettings>,
+) {
+    commands.spawn((
+        RatatuiCamera::autoresize(),
+        Camera2d,
+        OrthographicProjection {
+            scale: ORTHO_SCALING,
+            ..OrthographicProjection::default_2d()
+        },
+    ));
+
+    commands.insert_resource(ToasterSheets(
+        settings
+            .sheets
+            .iter()
+            .map(|sheet| LoadedSheet {
+                image: asset_server.load(&sheet.path),
+                frames: sheet.frames,
+                layout: None,
+                size: Vec2::splat(FLYER_SIZE),
+            })
+            .collect(),
+    ));
+}
+
+/// Splits each sheet into frames once its image has loaded, or stops if one can't be loaded.
+fn sheet_layout_system(
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut sheets: ResMut<ToasterSheets>,
+    message: Res<ExitMessage>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for sheet in sheets.0.iter_mut().filter(|sheet| sheet.layout.is_none()) {
+        if let LoadState::Failed(error) = asset_server.load_state(&sheet.image) {
+            if let Ok(mut message) = message.lock() {
+                *message = Some(format!("couldn't load toasters sheet: {error}"));
+            }
+            exit.send(AppExit::error());
+            return;
+        }
+
+        let Some(image) = images.get(&sheet.image) else {
+            continue;
+        };
+
+        let frame_size = UVec2::new((image.width() / sheet.frames).max(1), image.height());
+        sheet.size = frame_size.as_vec2() / frame_size.max_element() as f32 * FLYER_SIZE;
+        sheet.layout = Some(layouts.add(TextureAtlasLayout::from_grid(
+            frame_size,
+            sheet.frames,
+            1,
+            None,
+            None,
+        )));
+    }
+}
+
+/// Scatters the flyers over the screen as soon as every sheet is ready and the size of the
+/// terminal is known. From then on, the same flyers keep coming back around.
+fn flyer_spawn_system(
+    mut commands: Commands,
+    viewport: Res<Viewport>,
+    settings: Res<ToastersSettings>,
+    sheets: Res<ToasterSheets>,
+    mut rng: ResMut<ToasterRng>,
+    mut spawned: Local<bool>,
+) {
+    if *spawned || viewport.width == 0 || viewport.height == 0 {
+        return;
+    }
+
+    let Some(layouts) = sheets
+        .0
+        .iter()
+        .map(|sheet| sheet.layout.clone())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+
+    let region = visible_region(&viewport);
+    let flyers = sheets.0.iter().zip(layouts).cycle();
+    for (index, (sheet, layout)) in flyers.take(settings.count as usize).enumerate() {
+        let layer = index as u32 % LAYERS;
+        // Farther layers are smaller, slower and dimmer, which makes the scene look deeper.
+        let depth = (layer + 1) as f32 / LAYERS as f32;
+        let brightness = 0.4 + 0.6 * depth;
+        let position = Vec2::new(
+            rng.gen_range(-region.x..region.x),
+            rng.gen_range(-region.y..region.y),
+        ) / 2.;
+
+        commands.spawn((
+            Flyer {
+                velocity: FLYER_HEADING.normalize() * FLYER_SPEED * settings.speed * depth,
+                frames: sheet.frames as usize,
+                frame_timer: Timer::from_seconds(FRAME_SECONDS, TimerMode::Repeating),
+            },
+            Sprite {
+                image: sheet.image.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout,
+                    index: rng.gen_range(0..sheet.frames as usize),
+                }),
+                color: Color::srgb(brightness, brightness, brightness),
+                custom_size: Some(sheet.size * depth),
+                ..default()
+            },
+            Transform::from_translation(position.extend(layer as f32)),
+        ));
+    }
+
+    *spawned = true;
+}
+
+/// Moves the flyers along, bringing back the ones that have left the screen at the top or right.
+fn flyer_movement_system(
+    time: Res<Time>,
+    viewport: Res<Viewport>,
+    mut rng: ResMut<ToasterRng>,
+    mut flyers: Query<(&mut Transform, &Flyer)>,
+) {
+    let region = visible_region(&viewport);
+    let bounds = region / 2. + FLYER_SIZE;
+
+    for (mut transform, flyer) in &mut flyers {
+        transform.translation += (flyer.velocity * time.delta_secs()).extend(0.);
+
+        if transform.translation.x < -bounds.x || transform.translation.y < -bounds.y {
+            // Anywhere along the top and right edges, in proportion to their lengths.
+            let along = rng.gen_range(0.0..region.x + region.y);
+            let position = if along < region.x {
+                Vec2::new(along - region.x / 2., bounds.y)
+            } else {
+                Vec2::new(bounds.x, region.y / 2. - (along - region.x))
+            };
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+}
+
+fn flyer_animation_system(time: Res<Time>, mut flyers: Query<(&mut Sprite, &mut Flyer)>) {
+    for (mut sprite, mut flyer) in &mut flyers {
+        if !flyer.frame_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = (atlas.index + 1) % flyer.frames;
+        }
+    }
+}
+
+/// Size of the area the camera shows, in world units.
+fn visible_region(viewport: &Viewport) -> Vec2 {
+    Vec2::new(viewport.width as f32 * 2., viewport.height as f32 * 4.) * ORTHO_SCALING
+}
@@ -0,0 +1,82 @@
+use bevy::app::PluginsState;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::prelude::*;
+use bevy::tasks::tick_global_task_pools_on_main_thread;
+use bevy_ratatui::event::{KeyEvent, MouseEvent, ResizeEvent};
+use bevy_ratatui_camera::RatatuiCameraWidget;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::widgets::Widget;
+
+use crate::common::{debug_overlay, render_scene, Glyphs, Overlay, Viewport};
+use crate::{build_screensaver, Flags, Settings};
+
+/// A screensaver drawn by another ratatui app, into whatever area it likes, without taking over
+/// the terminal. The host app calls [`ScreensaverWidget::update`] to step the screensaver, and
+/// renders it like any other widget.
+pub struct ScreensaverWidget {
+    app: App,
+}
+
+impl ScreensaverWidget {
+    pub fn new(settings: Settings) -> Self {
+        let mut app = App::new();
+
+        // Input and resizes come from the host app rather than the terminal, but the screensaver
+        // systems still read them.
+        app.add_event::<KeyEvent>()
+            .add_event::<MouseEvent>()
+            .add_event::<ResizeEvent>();
+
+        build_screensaver(&mut app, &settings);
+
+        // Normally done by the app runner, which isn't used because the host app steps the world.
+        while app.plugins_state() == PluginsState::Adding {
+            tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        Self { app }
+    }
+
+    /// Steps the screensaver forward by one frame.
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    /// The Bevy app running the screensaver, for sending it events or inspecting its world.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+impl Widget for &mut ScreensaverWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let world = self.app.world_mut();
+
+        // The camera renders at the size of the area on the next update, the same way it follows
+        // terminal resizes.
+        if world.resource::<Viewport>().0 != area.as_size() {
+            world.resource_mut::<Viewport>().0 = area.as_size();
+            world.send_event(ResizeEvent(area.as_size()));
+        }
+
+        let stats = world
+            .resource::<Flags>()
+            .debug
+            .then(|| debug_overlay(world.resource::<DiagnosticsStore>()));
+
+        let mut widgets = world.query::<&RatatuiCameraWidget>();
+        let mut glyphs = world.query::<&Glyphs>();
+        let mut overlays = world.query::<&Overlay>();
+
+        render_scene(
+            widgets.get_single(world).ok(),
+            glyphs.iter(world),
+            overlays.iter(world).chain(&stats),
+            area,
+            buf,
+        );
+    }
+}
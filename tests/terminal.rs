@@ -0,0 +1,211 @@
+#![cfg(unix)]
+
+use std::{
+    fs,
+    io::Read,
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+
+const ENTER_ALTERNATE_SCREEN: &[u8] = b"\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &[u8] = b"\x1b[?1049l";
+const SHOW_CURSOR: &[u8] = b"\x1b[?25h";
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// ttysvr running in a pseudo terminal, with its output read on another thread so that waiting
+/// for it can time out.
+struct Screensaver {
+    child: Box<dyn Child + Send + Sync>,
+    // Held on to so that the terminal stays open until ttysvr is done with it.
+    master: Box<dyn MasterPty + Send>,
+    output: Receiver<Vec<u8>>,
+    seen: Vec<u8>,
+}
+
+impl Screensaver {
+    fn spawn(args: &[&str]) -> Self {
+        let pty = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .expect("opening a pty");
+
+        let mut command = CommandBuilder::new(env!("CARGO_BIN_EXE_ttysvr"));
+        command.args(args);
+        command.env("TERM", "xterm-256color");
+        // Keeps any config or state of whoever runs the tests out of it.
+        command.env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"));
+        command.env("XDG_STATE_HOME", env!("CARGO_TARGET_TMPDIR"));
+
+        let child = pty.slave.spawn_command(command).expect("spawning ttysvr");
+        drop(pty.slave);
+
+        let mut reader = pty.master.try_clone_reader().expect("reading the pty");
+        let (sender, output) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            while let Ok(read @ 1..) = reader.read(&mut buffer) {
+                if sender.send(buffer[..read].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            child,
+            master: pty.master,
+            output,
+            seen: vec![],
+        }
+    }
+
+    /// Reads output until `sequence` has been written, returning whether it was before the
+    /// terminal closed or the time ran out.
+    fn wait_for(&mut self, sequence: &[u8]) -> bool {
+        let deadline = Instant::now() + TIMEOUT;
+
+        while !contains(&self.seen, sequence) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.output.recv_timeout(remaining) {
+                Ok(chunk) => self.seen.extend(chunk),
+                Err(_) => return false,
+            }
+        }
+
+        true
+    }
+
+    fn signal(&mut self, signal: libc::c_int) {
+        let pid = self.child.process_id().expect("ttysvr has a pid");
+        // SAFETY: sending a signal has no memory safety requirements.
+        unsafe { libc::kill(pid as libc::pid_t, signal) };
+    }
+
+    /// Whether the terminal is in its usual line by line mode, with typed characters echoed, rather
+    /// than the raw mode the screensaver puts it in.
+    fn is_cooked(&self) -> bool {
+        let fd = self
+            .master
+            .as_raw_fd()
+            .expect("the pty has a file descriptor");
+        let mut termios = MaybeUninit::uninit();
+        // SAFETY: `termios` is only read once `tcgetattr` has filled it in.
+        let termios = unsafe {
+            assert_eq!(
+                libc::tcgetattr(fd, termios.as_mut_ptr()),
+                0,
+                "reading the termios"
+            );
+            termios.assume_init()
+        };
+
+        let cooked = libc::ICANON | libc::ECHO;
+        termios.c_lflag & cooked == cooked
+    }
+
+    /// Output since the alternate screen was entered, when ttysvr set up the terminal.
+    fn since_setup(&self) -> &[u8] {
+        let start = find(&self.seen, ENTER_ALTERNATE_SCREEN).unwrap_or(0);
+        &self.seen[start..]
+    }
+}
+
+impl Drop for Screensaver {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    find(haystack, needle).is_some()
+}
+
+/// An image wider than any GPU takes as a texture, which loads but panics once it's drawn.
+fn oversized_image() -> PathBuf {
+    const WIDTH: usize = 1 << 17;
+
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("oversized.hdr");
+    let header = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X {WIDTH}\n");
+    let mut image = header.into_bytes();
+    // A single row of uncompressed grey pixels.
+    image.extend([128, 128, 128, 129].repeat(WIDTH));
+    fs::write(&path, image).expect("writing the image");
+
+    path
+}
+
+fn assert_restored_after(signal: libc::c_int) {
+    let mut screensaver = Screensaver::spawn(&["matrix"]);
+    assert!(
+        screensaver.wait_for(ENTER_ALTERNATE_SCREEN),
+        "ttysvr never took over the terminal"
+    );
+    // Raw mode comes just after the alternate screen.
+    let deadline = Instant::now() + TIMEOUT;
+    while screensaver.is_cooked() {
+        assert!(Instant::now() < deadline, "ttysvr never enabled raw mode");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    screensaver.signal(signal);
+    assert_restored(screensaver, &format!("signal {signal}"));
+}
+
+/// Waits for ttysvr to exit, checking that it failed and left the terminal as it found it.
+fn assert_restored(mut screensaver: Screensaver, cause: &str) {
+    let status = screensaver.child.wait().expect("waiting for ttysvr");
+    screensaver.wait_for(SHOW_CURSOR);
+
+    assert!(!status.success(), "ttysvr exited with {status:?}");
+    for (name, sequence) in [
+        ("leave the alternate screen", LEAVE_ALTERNATE_SCREEN),
+        ("show the cursor", SHOW_CURSOR),
+    ] {
+        assert!(
+            contains(screensaver.since_setup(), sequence),
+            "ttysvr didn't {name} after {cause}"
+        );
+    }
+    assert!(
+        screensaver.is_cooked(),
+        "ttysvr didn't disable raw mode after {cause}"
+    );
+}
+
+#[test]
+fn terminal_is_restored_after_sigterm() {
+    assert_restored_after(libc::SIGTERM);
+}
+
+#[test]
+fn terminal_is_restored_after_sighup() {
+    assert_restored_after(libc::SIGHUP);
+}
+
+#[test]
+fn terminal_is_restored_after_sigint() {
+    assert_restored_after(libc::SIGINT);
+}
+
+#[test]
+fn terminal_is_restored_after_a_panic() {
+    let image = oversized_image();
+    let mut screensaver = Screensaver::spawn(&["logo", "--image", image.to_str().unwrap()]);
+
+    assert!(screensaver.wait_for(b"panicked"), "ttysvr didn't panic");
+    assert_restored(screensaver, "a panic");
+}